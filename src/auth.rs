@@ -0,0 +1,351 @@
+//! Request authentication.
+//!
+//! Requests may carry an optional [`Auth`] envelope, containing either a bearer token
+//! or an HMAC-SHA256 over the request kind, bound to the request `req_id` (used as a nonce)
+//! and a timestamp. Daemons verify requests using an [`Authenticator`], responding with
+//! `ResponseKind::Unauthorized` where verification fails.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::{Request, RequestKind};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Default maximum clock skew for HMAC authenticated requests
+pub const DEFAULT_MAX_SKEW: Duration = Duration::from_secs(30);
+
+/// Authenticated client identity
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Principal(pub String);
+
+impl fmt::Display for Principal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Authentication envelope attached to a request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Auth {
+    /// Bearer token
+    Token(String),
+    /// HMAC-SHA256 over the request
+    Hmac {
+        /// Client identifier for key lookup
+        client: String,
+        /// Seconds since the unix epoch at which the request was signed
+        timestamp: u64,
+        /// Message authentication code
+        mac: Vec<u8>,
+    },
+}
+
+/// Client credentials, used to authenticate outgoing requests
+#[derive(Clone)]
+pub enum Credentials {
+    /// Bearer token
+    Token(String),
+    /// Shared HMAC key
+    Key { client: String, key: Vec<u8> },
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Redact secrets
+        match self {
+            Credentials::Token(_) => write!(f, "Token(..)"),
+            Credentials::Key { client, .. } => write!(f, "Key {{ client: {:?}, .. }}", client),
+        }
+    }
+}
+
+impl Credentials {
+    /// Apply credentials to a request
+    pub fn apply(&self, req: Request) -> Request {
+        match self {
+            Credentials::Token(t) => req.with_token(t),
+            Credentials::Key { client, key } => req.sign(client, key),
+        }
+    }
+}
+
+/// Errors returned when verifying request authentication
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AuthError {
+    /// Request has no authentication envelope
+    Missing,
+    /// Bearer token not recognised
+    InvalidToken,
+    /// HMAC client not recognised
+    UnknownClient,
+    /// HMAC verification failed
+    InvalidMac,
+    /// Request timestamp outside of the allowed window
+    Expired,
+    /// Request nonce has already been used
+    Replayed,
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthError::Missing => write!(f, "missing authentication"),
+            AuthError::InvalidToken => write!(f, "invalid token"),
+            AuthError::UnknownClient => write!(f, "unknown client"),
+            AuthError::InvalidMac => write!(f, "invalid mac"),
+            AuthError::Expired => write!(f, "request expired"),
+            AuthError::Replayed => write!(f, "request replayed"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Compute the HMAC for a request.
+///
+/// This covers the `req_id`, timestamp, client identifier and JSON encoded request kind.
+/// Daemons re-encode the deserialised request kind for verification, so fields added
+/// (or defaulted) between crate versions cause verification to fail. HMAC authentication
+/// requires clients and daemons using the same version of this crate.
+fn request_mac(
+    key: &[u8],
+    req_id: u64,
+    timestamp: u64,
+    client: &str,
+    kind: &RequestKind,
+) -> HmacSha256 {
+    let data = serde_json::to_vec(&(req_id, timestamp, client, kind))
+        .expect("request kinds are always serializable");
+
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&data);
+    mac
+}
+
+/// Current time in seconds since the unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Constant time comparison of byte slices.
+///
+/// Slices are compared via fixed length digests, so mismatched lengths do not return early
+/// (leaking the length of the expected value).
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    let (a, b) = (Sha256::digest(a), Sha256::digest(b));
+
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+impl Request {
+    /// Attach a bearer token to the request
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.auth = Some(Auth::Token(token.to_string()));
+        self
+    }
+
+    /// Sign the request using HMAC-SHA256 with the provided client identifier and key
+    pub fn sign(mut self, client: &str, key: &[u8]) -> Self {
+        let timestamp = now();
+        let mac = request_mac(key, self.req_id, timestamp, client, &self.kind);
+
+        self.auth = Some(Auth::Hmac {
+            client: client.to_string(),
+            timestamp,
+            mac: mac.finalize().into_bytes().to_vec(),
+        });
+        self
+    }
+
+    /// Fetch the authentication envelope for the request
+    pub fn auth(&self) -> Option<&Auth> {
+        self.auth.as_ref()
+    }
+}
+
+/// Daemon side request authenticator
+#[derive(Clone)]
+pub struct Authenticator {
+    tokens: Vec<(String, Principal)>,
+    keys: HashMap<String, Vec<u8>>,
+    max_skew: Duration,
+    seen: HashMap<(String, u64), u64>,
+}
+
+impl Default for Authenticator {
+    fn default() -> Self {
+        Self {
+            tokens: vec![],
+            keys: HashMap::new(),
+            max_skew: DEFAULT_MAX_SKEW,
+            seen: HashMap::new(),
+        }
+    }
+}
+
+impl Authenticator {
+    /// Register a bearer token for the provided principal
+    pub fn with_token(mut self, token: &str, principal: Principal) -> Self {
+        self.tokens.push((token.to_string(), principal));
+        self
+    }
+
+    /// Register an HMAC key for the provided client, authenticated as a principal of the same name
+    pub fn with_key(mut self, client: &str, key: &[u8]) -> Self {
+        self.keys.insert(client.to_string(), key.to_vec());
+        self
+    }
+
+    /// Set the maximum allowed clock skew for HMAC authenticated requests
+    pub fn with_max_skew(mut self, max_skew: Duration) -> Self {
+        self.max_skew = max_skew;
+        self
+    }
+
+    /// Verify the authentication envelope for a request, returning the authenticated principal
+    pub fn verify(&mut self, req: &Request) -> Result<Principal, AuthError> {
+        match &req.auth {
+            None => Err(AuthError::Missing),
+            Some(Auth::Token(t)) => {
+                // Check all tokens to avoid leaking timing information
+                let mut principal = None;
+                for (token, p) in &self.tokens {
+                    if ct_eq(token.as_bytes(), t.as_bytes()) {
+                        principal = Some(p.clone());
+                    }
+                }
+                principal.ok_or(AuthError::InvalidToken)
+            }
+            Some(Auth::Hmac {
+                client,
+                timestamp,
+                mac,
+            }) => {
+                let key = self.keys.get(client).ok_or(AuthError::UnknownClient)?;
+
+                request_mac(key, req.req_id, *timestamp, client, &req.kind)
+                    .verify_slice(mac)
+                    .map_err(|_| AuthError::InvalidMac)?;
+
+                let now = now();
+                let skew = self.max_skew.as_secs();
+                if timestamp.saturating_add(skew) < now || *timestamp > now.saturating_add(skew) {
+                    return Err(AuthError::Expired);
+                }
+
+                // Expire nonces outside the skew window, then check for replays
+                self.seen.retain(|_, t| t.saturating_add(skew) >= now);
+                if self
+                    .seen
+                    .insert((client.clone(), req.req_id), *timestamp)
+                    .is_some()
+                {
+                    return Err(AuthError::Replayed);
+                }
+
+                Ok(Principal(client.clone()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"test key";
+
+    fn authenticator() -> Authenticator {
+        Authenticator::default()
+            .with_token("token", Principal("user".to_string()))
+            .with_key("client", KEY)
+    }
+
+    /// Sign a request with the provided timestamp
+    fn sign_at(mut req: Request, key: &[u8], timestamp: u64) -> Request {
+        let mac = request_mac(key, req.req_id, timestamp, "client", &req.kind);
+
+        req.auth = Some(Auth::Hmac {
+            client: "client".to_string(),
+            timestamp,
+            mac: mac.finalize().into_bytes().to_vec(),
+        });
+        req
+    }
+
+    #[test]
+    fn verify_token() {
+        let mut a = authenticator();
+
+        let req = Request::new(RequestKind::Status).with_token("token");
+        assert_eq!(a.verify(&req), Ok(Principal("user".to_string())));
+
+        let req = Request::new(RequestKind::Status).with_token("tokens");
+        assert_eq!(a.verify(&req), Err(AuthError::InvalidToken));
+
+        let req = Request::new(RequestKind::Status);
+        assert_eq!(a.verify(&req), Err(AuthError::Missing));
+    }
+
+    #[test]
+    fn verify_hmac() {
+        let mut a = authenticator();
+
+        let req = Request::new(RequestKind::Status).sign("client", KEY);
+        assert_eq!(a.verify(&req), Ok(Principal("client".to_string())));
+
+        let req = Request::new(RequestKind::Status).sign("client", b"wrong key");
+        assert_eq!(a.verify(&req), Err(AuthError::InvalidMac));
+
+        let req = Request::new(RequestKind::Status).sign("other", KEY);
+        assert_eq!(a.verify(&req), Err(AuthError::UnknownClient));
+
+        // Modified requests fail verification
+        let mut req = Request::new(RequestKind::Status).sign("client", KEY);
+        req.req_id = req.req_id.wrapping_add(1);
+        assert_eq!(a.verify(&req), Err(AuthError::InvalidMac));
+    }
+
+    #[test]
+    fn verify_hmac_timestamps() {
+        let mut a = authenticator().with_max_skew(Duration::from_secs(30));
+        let n = now();
+
+        let req = sign_at(Request::new(RequestKind::Status), KEY, n - 20);
+        assert!(a.verify(&req).is_ok());
+
+        let req = sign_at(Request::new(RequestKind::Status), KEY, n - 60);
+        assert_eq!(a.verify(&req), Err(AuthError::Expired));
+
+        let req = sign_at(Request::new(RequestKind::Status), KEY, n + 60);
+        assert_eq!(a.verify(&req), Err(AuthError::Expired));
+
+        // Timestamps must not overflow skew checks
+        let req = sign_at(Request::new(RequestKind::Status), KEY, u64::MAX);
+        assert_eq!(a.verify(&req), Err(AuthError::Expired));
+    }
+
+    #[test]
+    fn verify_hmac_replay() {
+        let mut a = authenticator();
+
+        let req = Request::new(RequestKind::Status).sign("client", KEY);
+        assert!(a.verify(&req).is_ok());
+        assert_eq!(a.verify(&req), Err(AuthError::Replayed));
+
+        // Re-signing does not permit re-use of a req_id
+        let req = req.sign("client", KEY);
+        assert_eq!(a.verify(&req), Err(AuthError::Replayed));
+    }
+}
@@ -0,0 +1,502 @@
+//! Unix domain socket client for issuing RPC requests to the daemon.
+//!
+//! Requests are multiplexed over a single connection, with responses
+//! matched to outstanding requests by `req_id`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Error as IoError;
+use std::net::Shutdown;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use async_std::os::unix::net::UnixStream;
+use async_std::task;
+
+use async_trait::async_trait;
+use asynchronous_codec::{FramedRead, FramedWrite};
+use futures::channel::{mpsc, oneshot};
+use futures::lock::Mutex as AsyncMutex;
+use futures::prelude::*;
+
+use crate::codec::{ClientCodec, CodecError, Framing, RpcCodec};
+use crate::{Credentials, Request, Response, ResponseStream, Rpc, StreamRpc, UnexpectedResponse};
+
+/// Default path for the daemon socket
+pub const DEFAULT_SOCKET: &str = "/tmp/dsf.sock";
+
+/// Errors returned by the RPC client
+#[derive(Debug)]
+pub enum ClientError {
+    /// Underlying IO error
+    Io(IoError),
+    /// Error encoding or decoding messages
+    Codec(CodecError),
+    /// Connection to the daemon closed prior to receiving a response
+    Disconnected,
+    /// Daemon returned an unexpected response
+    Unexpected(UnexpectedResponse),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::Io(e) => write!(f, "io error: {}", e),
+            ClientError::Codec(e) => write!(f, "codec error: {}", e),
+            ClientError::Disconnected => write!(f, "daemon disconnected"),
+            ClientError::Unexpected(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<IoError> for ClientError {
+    fn from(e: IoError) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+impl From<CodecError> for ClientError {
+    fn from(e: CodecError) -> Self {
+        ClientError::Codec(e)
+    }
+}
+
+impl From<UnexpectedResponse> for ClientError {
+    fn from(e: UnexpectedResponse) -> Self {
+        ClientError::Unexpected(e)
+    }
+}
+
+/// Codec for incoming responses, decoded to JSON values so undecodable responses
+/// can still be matched to outstanding requests
+type ResponseCodec = RpcCodec<Request, serde_json::Value>;
+
+/// Outstanding requests awaiting responses, `None` once the connection has closed
+type Pending = Arc<Mutex<Option<HashMap<u64, Waiter>>>>;
+
+/// Handle for delivering responses to an outstanding request
+enum Waiter {
+    /// Single response request
+    Single(oneshot::Sender<Result<Response, ClientError>>),
+    /// Streaming request, open until a stream terminating response is received
+    Stream(mpsc::UnboundedSender<Response>),
+}
+
+/// RPC client connected to the daemon via a unix domain socket.
+///
+/// Clones share the underlying connection, allowing concurrent requests.
+#[derive(Clone)]
+pub struct UnixClient {
+    conn: Arc<Connection>,
+    pending: Pending,
+    credentials: Option<Credentials>,
+}
+
+/// Shared connection, shut down once all client handles are dropped
+struct Connection {
+    stream: UnixStream,
+    sink: AsyncMutex<FramedWrite<UnixStream, ClientCodec>>,
+}
+
+impl UnixClient {
+    /// Connect to the daemon socket at the provided path
+    pub async fn connect<P: AsRef<Path>>(path: P) -> Result<Self, ClientError> {
+        let stream = UnixStream::connect(path.as_ref()).await?;
+        Ok(Self::from_stream(stream, Framing::default()))
+    }
+
+    /// Create a client from an existing (connected) stream with the provided framing
+    pub fn from_stream(stream: UnixStream, framing: Framing) -> Self {
+        let codec = ClientCodec::new(framing);
+
+        let sink = FramedWrite::new(stream.clone(), codec);
+        let source = FramedRead::new(stream.clone(), ResponseCodec::new(framing));
+
+        let pending = Arc::new(Mutex::new(Some(HashMap::new())));
+
+        task::spawn(Self::dispatch(source, pending.clone()));
+
+        Self {
+            conn: Arc::new(Connection {
+                stream,
+                sink: AsyncMutex::new(sink),
+            }),
+            pending,
+            credentials: None,
+        }
+    }
+
+    /// Authenticate all requests issued by this client with the provided credentials
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Route incoming responses to the matching outstanding requests
+    async fn dispatch(mut source: FramedRead<UnixStream, ResponseCodec>, pending: Pending) {
+        while let Some(r) = source.next().await {
+            // Frames that are not valid JSON cannot be matched to a request, so terminate the connection
+            let v = match r {
+                Ok(v) => v,
+                Err(_) => break,
+            };
+            let req_id = v.get("req_id").and_then(|id| id.as_u64());
+
+            let mut pending = pending.lock().unwrap();
+            let p = match pending.as_mut() {
+                Some(p) => p,
+                None => break,
+            };
+
+            // Responses that cannot be decoded (e.g. new response kinds from a newer daemon)
+            // fail the matching request, dropping stream waiters terminates the stream
+            let resp = match serde_json::from_value::<Response>(v) {
+                Ok(resp) => resp,
+                Err(e) => match req_id {
+                    Some(id) => {
+                        if let Some(Waiter::Single(tx)) = p.remove(&id) {
+                            let _ = tx.send(Err(CodecError::Json(e).into()));
+                        }
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+
+            // Responses without a matching request are discarded
+            match p.remove(&resp.req_id()) {
+                Some(Waiter::Single(tx)) => {
+                    let _ = tx.send(Ok(resp));
+                }
+                Some(Waiter::Stream(tx)) => {
+                    let req_id = resp.req_id();
+                    let end = resp.kind.is_stream_end();
+
+                    // Streams remain registered until terminated or dropped by the receiver
+                    if tx.unbounded_send(resp).is_ok() && !end {
+                        p.insert(req_id, Waiter::Stream(tx));
+                    }
+                }
+                None => (),
+            }
+        }
+
+        // Dropping outstanding senders notifies waiting requests of the disconnect
+        pending.lock().unwrap().take();
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // Shutting down the socket terminates the dispatch task
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+impl UnixClient {
+    /// Register a waiter and send the associated request
+    async fn send(&self, req: Request, waiter: Waiter) -> Result<(), ClientError> {
+        let req = match &self.credentials {
+            Some(c) => c.apply(req),
+            None => req,
+        };
+        let req_id = req.req_id();
+
+        match self.pending.lock().unwrap().as_mut() {
+            Some(p) => p.insert(req_id, waiter),
+            None => return Err(ClientError::Disconnected),
+        };
+
+        if let Err(e) = self.conn.sink.lock().await.send(req).await {
+            if let Some(p) = self.pending.lock().unwrap().as_mut() {
+                p.remove(&req_id);
+            }
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Rpc for UnixClient {
+    type Error = ClientError;
+
+    async fn exec(&mut self, req: Request) -> Result<Response, Self::Error> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send(req, Waiter::Single(tx)).await?;
+
+        rx.await.unwrap_or(Err(ClientError::Disconnected))
+    }
+}
+
+#[async_trait]
+impl StreamRpc for UnixClient {
+    async fn exec_stream(&mut self, req: Request) -> Result<ResponseStream, Self::Error> {
+        let (tx, rx) = mpsc::unbounded();
+        let req_id = req.req_id();
+
+        self.send(req, Waiter::Stream(tx)).await?;
+
+        Ok(Box::pin(ClientStream {
+            req_id,
+            rx,
+            client: self.clone(),
+            done: false,
+        }))
+    }
+}
+
+/// Stream of responses to a streaming request, cancelled on the daemon if dropped
+/// prior to receiving a stream terminating response
+struct ClientStream {
+    req_id: u64,
+    rx: mpsc::UnboundedReceiver<Response>,
+    client: UnixClient,
+    done: bool,
+}
+
+impl Stream for ClientStream {
+    type Item = Response;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Response>> {
+        let r = self.rx.poll_next_unpin(cx);
+
+        if let Poll::Ready(Some(resp)) = &r {
+            if resp.kind.is_stream_end() {
+                self.done = true;
+            }
+        }
+
+        r
+    }
+}
+
+impl Drop for ClientStream {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+
+        // Stop routing responses to the stream, then request the daemon cancel it
+        if let Some(p) = self.client.pending.lock().unwrap().as_mut() {
+            p.remove(&self.req_id);
+        }
+
+        let mut client = self.client.clone();
+        let req_id = self.req_id;
+        task::spawn(async move {
+            let _ = client.stream_cancel(req_id).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use asynchronous_codec::Framed;
+
+    use crate::codec::{self, ServerCodec};
+    use crate::{Qos, RequestKind, ResponseKind, ServiceIdentifier, StreamCancelOptions};
+    use crate::{StreamRpc, SubscriptionInfo, SubscriptionKind};
+
+    /// Create a client connected to an in-process daemon connection
+    fn pair() -> (UnixClient, Framed<UnixStream, ServerCodec>) {
+        let (a, b) = UnixStream::pair().unwrap();
+        let client = UnixClient::from_stream(a, Framing::Line);
+
+        (client, codec::server(b, Framing::Line))
+    }
+
+    /// Write a raw (line delimited) frame to the client
+    async fn write_raw(daemon: &Framed<UnixStream, ServerCodec>, frame: &[u8]) {
+        let mut stream: &UnixStream = daemon;
+        stream.write_all(frame).await.unwrap();
+    }
+
+    #[test]
+    fn multiplex_requests() {
+        task::block_on(async {
+            let (client, mut daemon) = pair();
+
+            let (mut c1, mut c2) = (client.clone(), client.clone());
+            let r1 = Request::new(RequestKind::Status);
+            let r2 = Request::new(RequestKind::Status);
+            let (id1, id2) = (r1.req_id(), r2.req_id());
+
+            let t1 = task::spawn(async move { c1.exec(r1).await });
+            let t2 = task::spawn(async move { c2.exec(r2).await });
+
+            // Respond in reverse order to the requests received
+            let a = daemon.next().await.unwrap().unwrap();
+            let b = daemon.next().await.unwrap().unwrap();
+            for req in [b, a] {
+                let resp = Response::new(req.req_id(), ResponseKind::None);
+                daemon.send(resp).await.unwrap();
+            }
+
+            assert_eq!(t1.await.unwrap(), Response::new(id1, ResponseKind::None));
+            assert_eq!(t2.await.unwrap(), Response::new(id2, ResponseKind::None));
+        });
+    }
+
+    #[test]
+    fn disconnect_fails_requests() {
+        task::block_on(async {
+            let (mut client, mut daemon) = pair();
+
+            let mut c = client.clone();
+            let t = task::spawn(async move { c.exec(Request::new(RequestKind::Status)).await });
+
+            // Close the connection once the request is received
+            daemon.next().await.unwrap().unwrap();
+            drop(daemon);
+
+            assert!(matches!(t.await, Err(ClientError::Disconnected)));
+
+            let r = client.exec(Request::new(RequestKind::Status)).await;
+            assert!(matches!(r, Err(ClientError::Disconnected)));
+        });
+    }
+
+    #[test]
+    fn undecodable_response_fails_request() {
+        task::block_on(async {
+            let (client, mut daemon) = pair();
+
+            let mut c = client.clone();
+            let t = task::spawn(async move { c.exec(Request::new(RequestKind::Status)).await });
+
+            // Respond with a response kind unknown to the client
+            let req = daemon.next().await.unwrap().unwrap();
+            let raw = format!(
+                "{{\"req_id\":{},\"kind\":\"FromTheFuture\"}}\n",
+                req.req_id()
+            );
+            write_raw(&daemon, raw.as_bytes()).await;
+
+            assert!(matches!(
+                t.await,
+                Err(ClientError::Codec(CodecError::Json(_)))
+            ));
+
+            // The connection remains usable for following requests
+            let mut c = client.clone();
+            let t = task::spawn(async move { c.exec(Request::new(RequestKind::Status)).await });
+
+            let req = daemon.next().await.unwrap().unwrap();
+            let resp = Response::new(req.req_id(), ResponseKind::None);
+            daemon.send(resp.clone()).await.unwrap();
+
+            assert_eq!(t.await.unwrap(), resp);
+        });
+    }
+
+    #[test]
+    fn malformed_frame_closes_connection() {
+        task::block_on(async {
+            let (client, mut daemon) = pair();
+
+            let mut c = client.clone();
+            let t = task::spawn(async move { c.exec(Request::new(RequestKind::Status)).await });
+
+            // Frames that are not JSON can not be matched to a request
+            daemon.next().await.unwrap().unwrap();
+            write_raw(&daemon, b"not json\n").await;
+
+            assert!(matches!(t.await, Err(ClientError::Disconnected)));
+        });
+    }
+
+    #[test]
+    fn dropped_stream_is_cancelled() {
+        task::block_on(async {
+            let (mut client, mut daemon) = pair();
+
+            let req = Request::new(RequestKind::Stream(ServiceIdentifier::index(0).into()));
+            let req_id = req.req_id();
+            let mut s = client.exec_stream(req).await.unwrap();
+
+            daemon.next().await.unwrap().unwrap();
+            let item = Response::new(req_id, ResponseKind::None);
+            daemon.send(item.clone()).await.unwrap();
+            assert_eq!(s.next().await, Some(item));
+
+            // Dropping the incomplete stream cancels it on the daemon
+            drop(s);
+
+            let cancel = daemon.next().await.unwrap().unwrap();
+            let opts = StreamCancelOptions { req_id };
+            assert_eq!(cancel.kind(), RequestKind::StreamCancel(opts));
+        });
+    }
+
+    #[test]
+    fn completed_stream_is_not_cancelled() {
+        task::block_on(async {
+            let (mut client, mut daemon) = pair();
+
+            let req = Request::new(RequestKind::Stream(ServiceIdentifier::index(0).into()));
+            let req_id = req.req_id();
+            let mut s = client.exec_stream(req).await.unwrap();
+
+            daemon.next().await.unwrap().unwrap();
+            let end = Response::new(req_id, ResponseKind::StreamEnd);
+            daemon.send(end.clone()).await.unwrap();
+            assert_eq!(s.next().await, Some(end));
+            assert_eq!(s.next().await, None);
+
+            drop(s);
+
+            // The next request received follows the stream, with no cancellation
+            let mut c = client.clone();
+            task::spawn(async move { c.exec(Request::new(RequestKind::Status)).await });
+
+            let next = daemon.next().await.unwrap().unwrap();
+            assert_eq!(next.kind(), RequestKind::Status);
+        });
+    }
+
+    #[test]
+    fn stream_data_surfaces_errors() {
+        task::block_on(async {
+            let (client, mut daemon) = pair();
+
+            let mut c = client.clone();
+            let t = task::spawn(async move {
+                let opts = ServiceIdentifier::index(0).into();
+                let (_info, s) = c.stream_data(opts).await.unwrap();
+                s.collect::<Vec<_>>().await
+            });
+
+            let req = daemon.next().await.unwrap().unwrap();
+            let info = SubscriptionInfo {
+                service_id: Default::default(),
+                kind: SubscriptionKind::Socket(1),
+                updated: None,
+                expiry: None,
+                qos: Qos::default(),
+            };
+            for kind in [ResponseKind::StreamOpen(info), ResponseKind::Unauthorized] {
+                daemon
+                    .send(Response::new(req.req_id(), kind))
+                    .await
+                    .unwrap();
+            }
+
+            // Terminating errors are returned rather than ending the stream silently
+            let items = t.await;
+            assert_eq!(items.len(), 1);
+            assert_eq!(
+                items[0].as_ref().map_err(|e| e.got.as_ref()),
+                Err(&ResponseKind::Unauthorized)
+            );
+        });
+    }
+}
@@ -0,0 +1,361 @@
+//! Wire codecs for exchanging RPC messages over byte streams.
+//!
+//! Two framings are supported, both carrying JSON encoded objects:
+//! - `Length`: each frame is prefixed with a big-endian `u32` length
+//! - `Line`: each frame is terminated with a newline (`\n`)
+//!
+//! Codecs may be used with any `AsyncRead + AsyncWrite` via [`Framed`],
+//! which implements `futures::Stream` for decoded objects and `futures::Sink` for encoding.
+
+use std::fmt;
+use std::io::Error as IoError;
+use std::marker::PhantomData;
+
+use asynchronous_codec::{Decoder, Encoder, Framed};
+use bytes::{Buf, BufMut, BytesMut};
+use futures::io::{AsyncRead, AsyncWrite};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{Request, Response};
+
+/// Default maximum frame size (1 MiB)
+pub const DEFAULT_MAX_FRAME: usize = 1024 * 1024;
+
+/// Length of the frame header for length-prefixed framing
+const LENGTH_PREFIX_LEN: usize = 4;
+
+/// Framing used to delimit messages on a stream
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Framing {
+    /// Big-endian `u32` length prefixed JSON frames
+    #[default]
+    Length,
+    /// Newline-delimited JSON frames
+    Line,
+}
+
+/// Errors returned when encoding or decoding frames
+#[derive(Debug)]
+pub enum CodecError {
+    /// Underlying IO error
+    Io(IoError),
+    /// Frame exceeds the configured maximum frame size
+    FrameTooLarge { len: usize, max: usize },
+    /// Frame contents could not be encoded or decoded
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodecError::Io(e) => write!(f, "io error: {}", e),
+            CodecError::FrameTooLarge { len, max } => {
+                write!(f, "frame length {} exceeds maximum {}", len, max)
+            }
+            CodecError::Json(e) => write!(f, "json error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+impl From<IoError> for CodecError {
+    fn from(e: IoError) -> Self {
+        CodecError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for CodecError {
+    fn from(e: serde_json::Error) -> Self {
+        CodecError::Json(e)
+    }
+}
+
+/// Codec encoding objects of type `E` and decoding objects of type `D`
+pub struct RpcCodec<E, D> {
+    framing: Framing,
+    max_frame: usize,
+    _enc: PhantomData<fn(E)>,
+    _dec: PhantomData<fn() -> D>,
+}
+
+/// Client side codec, encodes [`Request`]s and decodes [`Response`]s
+pub type ClientCodec = RpcCodec<Request, Response>;
+
+/// Daemon side codec, encodes [`Response`]s and decodes [`Request`]s
+pub type ServerCodec = RpcCodec<Response, Request>;
+
+impl<E, D> RpcCodec<E, D> {
+    /// Create a new codec with the provided framing and the default maximum frame size
+    pub fn new(framing: Framing) -> Self {
+        Self {
+            framing,
+            max_frame: DEFAULT_MAX_FRAME,
+            _enc: PhantomData,
+            _dec: PhantomData,
+        }
+    }
+
+    /// Set the maximum frame size for encoding and decoding
+    pub fn with_max_frame(mut self, max_frame: usize) -> Self {
+        self.max_frame = max_frame;
+        self
+    }
+
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    pub fn max_frame(&self) -> usize {
+        self.max_frame
+    }
+}
+
+impl<E, D> Default for RpcCodec<E, D> {
+    fn default() -> Self {
+        Self::new(Framing::default())
+    }
+}
+
+impl<E, D> Clone for RpcCodec<E, D> {
+    fn clone(&self) -> Self {
+        Self::new(self.framing).with_max_frame(self.max_frame)
+    }
+}
+
+impl<E, D> fmt::Debug for RpcCodec<E, D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RpcCodec")
+            .field("framing", &self.framing)
+            .field("max_frame", &self.max_frame)
+            .finish()
+    }
+}
+
+impl<E: Serialize, D> Encoder for RpcCodec<E, D> {
+    type Item = E;
+    type Error = CodecError;
+
+    fn encode(&mut self, item: E, dst: &mut BytesMut) -> Result<(), CodecError> {
+        let data = serde_json::to_vec(&item)?;
+        if data.len() > self.max_frame {
+            return Err(CodecError::FrameTooLarge {
+                len: data.len(),
+                max: self.max_frame,
+            });
+        }
+
+        match self.framing {
+            Framing::Length => {
+                dst.reserve(LENGTH_PREFIX_LEN + data.len());
+                dst.put_u32(data.len() as u32);
+                dst.put_slice(&data);
+            }
+            Framing::Line => {
+                dst.reserve(data.len() + 1);
+                dst.put_slice(&data);
+                dst.put_u8(b'\n');
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<E, D: DeserializeOwned> Decoder for RpcCodec<E, D> {
+    type Item = D;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<D>, CodecError> {
+        let frame = match self.framing {
+            Framing::Length => {
+                if src.len() < LENGTH_PREFIX_LEN {
+                    return Ok(None);
+                }
+
+                let len = u32::from_be_bytes([src[0], src[1], src[2], src[3]]) as usize;
+                if len > self.max_frame {
+                    return Err(CodecError::FrameTooLarge {
+                        len,
+                        max: self.max_frame,
+                    });
+                }
+
+                if src.len() < LENGTH_PREFIX_LEN + len {
+                    src.reserve(LENGTH_PREFIX_LEN + len - src.len());
+                    return Ok(None);
+                }
+
+                src.advance(LENGTH_PREFIX_LEN);
+                src.split_to(len)
+            }
+            Framing::Line => {
+                // Skip any empty lines between frames
+                while src.first() == Some(&b'\n') {
+                    src.advance(1);
+                }
+
+                let len = match src.iter().position(|b| *b == b'\n') {
+                    Some(n) => n,
+                    None if src.len() > self.max_frame => {
+                        return Err(CodecError::FrameTooLarge {
+                            len: src.len(),
+                            max: self.max_frame,
+                        })
+                    }
+                    None => return Ok(None),
+                };
+
+                if len > self.max_frame {
+                    return Err(CodecError::FrameTooLarge {
+                        len,
+                        max: self.max_frame,
+                    });
+                }
+
+                let mut line = src.split_to(len + 1);
+                line.truncate(len);
+                line
+            }
+        };
+
+        let obj = serde_json::from_slice(&frame)?;
+        Ok(Some(obj))
+    }
+}
+
+/// Wrap a client connection with the provided framing
+pub fn client<T: AsyncRead + AsyncWrite>(io: T, framing: Framing) -> Framed<T, ClientCodec> {
+    Framed::new(io, ClientCodec::new(framing))
+}
+
+/// Wrap a daemon connection with the provided framing
+pub fn server<T: AsyncRead + AsyncWrite>(io: T, framing: Framing) -> Framed<T, ServerCodec> {
+    Framed::new(io, ServerCodec::new(framing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{RequestKind, ResponseKind};
+
+    fn round_trip(framing: Framing) {
+        let mut client = ClientCodec::new(framing);
+        let mut server = ServerCodec::new(framing);
+        let mut buff = BytesMut::new();
+
+        let req = Request::new(RequestKind::Status);
+        client.encode(req.clone(), &mut buff).unwrap();
+        assert_eq!(server.decode(&mut buff).unwrap(), Some(req));
+        assert!(buff.is_empty());
+
+        let resp = Response::new(12, ResponseKind::None);
+        server.encode(resp.clone(), &mut buff).unwrap();
+        assert_eq!(client.decode(&mut buff).unwrap(), Some(resp));
+        assert!(buff.is_empty());
+    }
+
+    #[test]
+    fn round_trip_length() {
+        round_trip(Framing::Length);
+    }
+
+    #[test]
+    fn round_trip_line() {
+        round_trip(Framing::Line);
+    }
+
+    #[test]
+    fn decode_multiple_frames() {
+        for framing in [Framing::Length, Framing::Line] {
+            let mut codec = ClientCodec::new(framing);
+            let mut server = ServerCodec::new(framing);
+            let mut buff = BytesMut::new();
+
+            let resps: Vec<_> = (0..3)
+                .map(|i| Response::new(i, ResponseKind::None))
+                .collect();
+            for r in &resps {
+                server.encode(r.clone(), &mut buff).unwrap();
+            }
+
+            for r in &resps {
+                assert_eq!(codec.decode(&mut buff).unwrap().as_ref(), Some(r));
+            }
+            assert_eq!(codec.decode(&mut buff).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn decode_partial_frames() {
+        for framing in [Framing::Length, Framing::Line] {
+            let mut codec = ServerCodec::new(framing);
+            let req = Request::new(RequestKind::Status);
+
+            let mut encoded = BytesMut::new();
+            ClientCodec::new(framing)
+                .encode(req.clone(), &mut encoded)
+                .unwrap();
+
+            // Frames are only returned once complete
+            let mut buff = BytesMut::new();
+            for b in &encoded[..encoded.len() - 1] {
+                buff.put_u8(*b);
+                assert_eq!(codec.decode(&mut buff).unwrap(), None);
+            }
+
+            buff.put_u8(encoded[encoded.len() - 1]);
+            assert_eq!(codec.decode(&mut buff).unwrap(), Some(req));
+        }
+    }
+
+    #[test]
+    fn encode_rejects_large_frames() {
+        for framing in [Framing::Length, Framing::Line] {
+            let mut codec = ClientCodec::new(framing).with_max_frame(8);
+            let mut buff = BytesMut::new();
+
+            let r = codec.encode(Request::new(RequestKind::Status), &mut buff);
+            assert!(matches!(r, Err(CodecError::FrameTooLarge { max: 8, .. })));
+            assert!(buff.is_empty());
+        }
+    }
+
+    #[test]
+    fn decode_rejects_large_frames() {
+        // Length prefixed frames are rejected on the header, prior to receiving the body
+        let mut codec = ServerCodec::new(Framing::Length).with_max_frame(16);
+        let mut buff = BytesMut::new();
+        buff.put_u32(17);
+
+        let r = codec.decode(&mut buff);
+        assert!(matches!(
+            r,
+            Err(CodecError::FrameTooLarge { len: 17, max: 16 })
+        ));
+
+        // Line delimited frames are rejected once the unterminated line exceeds the maximum
+        let mut codec = ServerCodec::new(Framing::Line).with_max_frame(16);
+        let mut buff = BytesMut::from(&[b'a'; 16][..]);
+        assert_eq!(codec.decode(&mut buff).unwrap(), None);
+
+        buff.put_u8(b'a');
+        let r = codec.decode(&mut buff);
+        assert!(matches!(
+            r,
+            Err(CodecError::FrameTooLarge { len: 17, max: 16 })
+        ));
+    }
+
+    #[test]
+    fn decode_invalid_json() {
+        let mut codec = ServerCodec::new(Framing::Line);
+        let mut buff = BytesMut::from(&b"not json\n"[..]);
+
+        assert!(matches!(codec.decode(&mut buff), Err(CodecError::Json(_))));
+        assert!(buff.is_empty());
+    }
+}
@@ -1,17 +1,26 @@
 use std::net::SocketAddr;
+use std::time::SystemTime;
 use structopt::StructOpt;
 
 use crate::helpers::try_parse_sock_addr;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
 pub enum ConfigCommands {
+    #[structopt(name = "list-addresses")]
+    /// List explicit, auto-detected and bound addresses for the daemon
+    ListAddresses,
+
     #[structopt(name = "add-address")]
-    /// TODO: Register an external address for use by the daemon
+    /// Register an external address for use by the daemon
     AddAddress(SocketAddress),
 
     #[structopt(name = "remove-address")]
-    /// TODO: De-register an external address for the daemon
+    /// De-register an external address for the daemon
     RemoveAddress(SocketAddress),
+
+    #[structopt(name = "set-preferred-address")]
+    /// Set the preferred external address advertised by the daemon
+    SetPreferredAddress(SocketAddress),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
@@ -20,3 +29,39 @@ pub struct SocketAddress {
     /// Peer socket address
     pub address: SocketAddr,
 }
+
+/// AddressKind describes how the daemon came to use an address
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum AddressKind {
+    /// Explicitly configured external address
+    Explicit,
+    /// Auto-detected external address, as observed by peers
+    Implicit,
+    /// Local listener address
+    Bound,
+}
+
+/// Address information returned by address configuration commands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddressInfo {
+    pub address: SocketAddr,
+    pub kind: AddressKind,
+
+    /// Address is preferred when advertising the daemon
+    pub preferred: bool,
+
+    /// Time the address was last observed, for auto-detected addresses
+    pub observed: Option<SystemTime>,
+}
+
+impl AddressInfo {
+    pub fn new(address: SocketAddr, kind: AddressKind) -> Self {
+        Self {
+            address,
+            kind,
+            preferred: false,
+            observed: None,
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
 use dsf_core::options::Filters;
 use dsf_core::prelude::MaybeEncrypted;
 use dsf_core::wire::Container;
@@ -7,7 +11,7 @@ use dsf_core::base::Body;
 use dsf_core::types::*;
 
 use crate::helpers::data_from_str;
-use crate::{PageBounds, TimeBounds, ServiceIdentifier};
+use crate::{Cursor, PageBounds, TimeBounds, ServiceIdentifier};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DataInfo {
@@ -47,11 +51,11 @@ pub enum DataCommands {
 
     #[structopt(name = "sync")]
     /// Synchronize service data
-    Update {},
+    Update(SyncOptions),
 
     #[structopt(name = "query")]
     /// Fetch data from a service
-    Query {},
+    Query(QueryOptions),
 
     #[structopt(name = "publish")]
     /// Publish data to a service
@@ -76,6 +80,244 @@ pub struct ListOptions {
     pub time_bounds: TimeBounds,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct QueryOptions {
+    #[structopt(flatten)]
+    pub service: ServiceIdentifier,
+
+    #[structopt(flatten)]
+    #[serde(default)]
+    pub page_bounds: PageBounds,
+
+    #[structopt(flatten)]
+    #[serde(default)]
+    pub time_bounds: TimeBounds,
+
+    #[structopt(short = "k", long = "kind", parse(try_from_str = data_kind_from_str))]
+    #[serde(default)]
+    /// Data kinds to match, by name (`generic`, `iot`) or application kind number
+    /// (matches all kinds if unset)
+    pub kinds: Vec<DataKind>,
+
+    #[structopt(long = "filter")]
+    #[serde(default)]
+    /// Body filters, `prefix:VALUE` or `json:PATH=VALUE` (all filters must match)
+    pub filters: Vec<BodyFilter>,
+}
+
+impl QueryOptions {
+    pub fn new(service: ServiceIdentifier) -> Self {
+        Self {
+            service,
+            page_bounds: PageBounds::default(),
+            time_bounds: TimeBounds::default(),
+            kinds: vec![],
+            filters: vec![],
+        }
+    }
+
+    /// Check whether a data kind matches the query
+    pub fn matches_kind(&self, kind: DataKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+
+    /// Check whether a data body matches all query filters
+    pub fn matches_body(&self, body: &Body) -> bool {
+        self.filters.iter().all(|f| f.matches(body))
+    }
+}
+
+/// Parse a data kind by name (`generic`, `iot`) or application kind number
+pub fn data_kind_from_str(s: &str) -> Result<DataKind, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "generic" => Ok(DataKind::Generic),
+        "iot" => Ok(DataKind::Iot),
+        v => v.parse().map(DataKind::Unknown).map_err(|_| {
+            format!(
+                "unrecognised data kind '{}', expected generic, iot or a kind number",
+                s
+            )
+        }),
+    }
+}
+
+/// Filter applied to cleartext data bodies, encrypted bodies never match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BodyFilter {
+    /// Body starts with the provided bytes
+    Prefix(Data),
+    /// Body is a JSON object with the provided value at a (dot separated) path
+    JsonPath {
+        path: String,
+        value: serde_json::Value,
+    },
+}
+
+impl BodyFilter {
+    /// Check whether a data body matches the filter
+    pub fn matches(&self, body: &Body) -> bool {
+        let data = match body {
+            Body::Cleartext(d) => d,
+            _ => return false,
+        };
+
+        match self {
+            BodyFilter::Prefix(p) => data.starts_with(p),
+            BodyFilter::JsonPath { path, value } => {
+                let obj: serde_json::Value = match serde_json::from_slice(data) {
+                    Ok(v) => v,
+                    Err(_) => return false,
+                };
+
+                obj.pointer(&json_pointer(path)) == Some(value)
+            }
+        }
+    }
+}
+
+/// Convert a dot separated path (optionally prefixed with `$.`) to a JSON pointer
+fn json_pointer(path: &str) -> String {
+    let path = path.trim_start_matches('$').trim_start_matches('.');
+
+    path.split('.')
+        .filter(|s| !s.is_empty())
+        .map(|s| format!("/{}", s.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+impl FromStr for BodyFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(prefix) = s.strip_prefix("prefix:") {
+            return Ok(BodyFilter::Prefix(data_from_str(prefix)));
+        }
+
+        if let Some(m) = s.strip_prefix("json:") {
+            let mut split = m.splitn(2, '=');
+            let (path, value) = match (split.next(), split.next()) {
+                (Some(p), Some(v)) => (p, v),
+                _ => return Err("json filter requires PATH=VALUE".to_string()),
+            };
+
+            // Values are parsed as JSON where possible, falling back to strings
+            let value = serde_json::from_str(value)
+                .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+
+            return Ok(BodyFilter::JsonPath {
+                path: path.to_string(),
+                value,
+            });
+        }
+
+        Err(format!(
+            "unrecognised filter '{}', expected prefix:VALUE or json:PATH=VALUE",
+            s
+        ))
+    }
+}
+
+impl fmt::Display for BodyFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BodyFilter::Prefix(p) => write!(f, "prefix:{}", String::from_utf8_lossy(p)),
+            BodyFilter::JsonPath { path, value } => write!(f, "json:{}={}", path, value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct SyncOptions {
+    #[structopt(flatten)]
+    pub service: ServiceIdentifier,
+
+    #[structopt(long = "from-index", group = "start")]
+    /// Page index from which to start synchronisation
+    pub from_index: Option<u16>,
+
+    #[structopt(long = "from-sig", group = "start")]
+    /// Page signature from which to start synchronisation
+    pub from_sig: Option<Signature>,
+
+    #[structopt(long = "peer")]
+    #[serde(default)]
+    /// Peers to synchronise from (defaults to known replicas)
+    pub peers: Vec<Id>,
+
+    #[structopt(long = "max-peers")]
+    /// Maximum number of peers to consult
+    pub max_peers: Option<usize>,
+}
+
+impl SyncOptions {
+    pub fn new(service: ServiceIdentifier) -> Self {
+        Self {
+            service,
+            from_index: None,
+            from_sig: None,
+            peers: vec![],
+            max_peers: None,
+        }
+    }
+}
+
+/// Synchronisation results, returned in response to a `DataCommands::Update` request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncInfo {
+    pub service: Id,
+
+    /// Number of pages fetched
+    pub fetched: usize,
+    /// Latest page index following synchronisation
+    pub latest: Option<u16>,
+    /// Gaps remaining in the `previous` signature chain
+    pub gaps: Vec<SyncGap>,
+    /// Peers consulted during synchronisation
+    pub peers: Vec<Id>,
+}
+
+/// Gap in a service's data chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncGap {
+    /// Index of the page referencing a missing previous page
+    pub index: u16,
+    /// Signature of the missing page
+    pub missing: Signature,
+}
+
+/// Find gaps in the `previous` signature chain for a set of data objects.
+///
+/// All available objects should be provided, as references from the earliest
+/// object to any prior (missing) object are reported as gaps.
+pub fn chain_gaps(data: &[DataInfo]) -> Vec<SyncGap> {
+    let known: HashSet<&Signature> = data.iter().map(|d| &d.signature).collect();
+
+    let mut gaps: Vec<_> = data
+        .iter()
+        .filter_map(|d| match &d.previous {
+            Some(p) if !known.contains(p) => Some(SyncGap {
+                index: d.index,
+                missing: p.clone(),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    gaps.sort_by_key(|g| g.index);
+    gaps
+}
+
+/// Query results, returned in response to a `DataCommands::Query` request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryInfo {
+    /// Matching data objects
+    pub data: Vec<DataInfo>,
+    /// Total number of matching objects
+    pub total: usize,
+    /// Cursor for fetching the next page of results, if available
+    pub next_cursor: Option<Cursor>,
+}
+
 pub type Data = Vec<u8>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
@@ -132,3 +374,30 @@ pub struct PublishInfo {
     pub index: u16,
     //pub sig: Signature,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_data_kinds() {
+        assert_eq!(data_kind_from_str("generic"), Ok(DataKind::Generic));
+        assert_eq!(data_kind_from_str("IoT"), Ok(DataKind::Iot));
+        assert_eq!(data_kind_from_str("17"), Ok(DataKind::Unknown(17)));
+        assert!(data_kind_from_str("sensor").is_err());
+    }
+
+    #[test]
+    fn query_kind_filters() {
+        let q = QueryOptions::from_iter_safe(&["query", "-n", "0", "--kind", "iot", "-k", "17"])
+            .unwrap();
+        assert_eq!(q.kinds, vec![DataKind::Iot, DataKind::Unknown(17)]);
+
+        assert!(q.matches_kind(DataKind::Iot));
+        assert!(q.matches_kind(DataKind::Unknown(17)));
+        assert!(!q.matches_kind(DataKind::Generic));
+
+        let q = QueryOptions::new(ServiceIdentifier::index(0));
+        assert!(q.matches_kind(DataKind::Generic));
+    }
+}
@@ -1,15 +1,106 @@
+//! Delegation of network operations from constrained devices.
+//!
+//! Devices without the resources to participate in the DHT sign their own pages and
+//! data objects, then delegate registration, publishing and discovery to a daemon.
+//! Registration responds with `ResponseKind::Registered`, publishing with
+//! `ResponseKind::Published` and hello requests with a signed `ResponseKind::Page`.
+
 use structopt::StructOpt;
 
+use dsf_core::types::*;
+
+use dsf_core::wire::Container;
+
+use crate::helpers::container_from_base64;
 use crate::ServiceIdentifier;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
 pub enum DelegationCommands {
-    #[structopt(name = "datastore")]
+    #[structopt(name = "hello")]
     /// Request a signed hello / discovery payload
     Hello,
 
-    /// Register a service
-    Register(Page),
+    #[structopt(name = "register")]
+    /// Register a service using a pre-signed page
+    Register(DelegateRegisterOptions),
+
+    #[structopt(name = "publish")]
+    /// Publish pre-signed data objects for a service
+    Publish(DelegatePublishOptions),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct DelegateRegisterOptions {
+    #[structopt(flatten)]
+    /// Service identifier, must match the ID of the pre-signed page
+    pub service: ServiceIdentifier,
+
+    #[structopt(short = "p", long = "page", parse(try_from_str = container_from_base64))]
+    /// Base64 encoded (pre-signed) DSF primary page
+    pub page: Container,
+
+    #[structopt(long = "no-replica")]
+    /// Do not become a replica for the registered service
+    pub no_replica: bool,
+}
+
+impl DelegateRegisterOptions {
+    pub fn new(id: Id, page: Container) -> Self {
+        Self {
+            service: ServiceIdentifier::id(id),
+            page,
+            no_replica: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct DelegatePublishOptions {
+    #[structopt(flatten)]
+    /// Service identifier, must match the ID of the pre-signed objects
+    pub service: ServiceIdentifier,
+
+    #[structopt(short = "d", long = "data", parse(try_from_str = container_from_base64))]
+    /// Base64 encoded (pre-signed) DSF data objects, in publishing order
+    pub data: Vec<Container>,
+}
+
+impl DelegatePublishOptions {
+    pub fn new(id: Id, data: Vec<Container>) -> Self {
+        Self {
+            service: ServiceIdentifier::id(id),
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_base64_objects() {
+        let (a, b) = (vec![1u8, 2, 3, 4], vec![5u8, 6, 7, 8]);
 
+        let opts = DelegatePublishOptions::from_iter_safe(&[
+            "publish",
+            "--id",
+            &Id::default().to_string(),
+            "--data",
+            &base64::encode(&a),
+            "--data",
+            &base64::encode(&b),
+        ])
+        .unwrap();
+        assert_eq!(opts.data, vec![Container::from(a), Container::from(b)]);
 
+        let r = DelegateRegisterOptions::from_iter_safe(&[
+            "register",
+            "--id",
+            &Id::default().to_string(),
+            "--page",
+            "not base64!",
+        ]);
+        assert!(r.is_err());
+    }
 }
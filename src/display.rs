@@ -1,9 +1,13 @@
 use std::fmt::{Display, Formatter, Result};
 use std::net::SocketAddr;
+use std::time::SystemTime;
 
 use colored::Colorize;
 
-use crate::{DataInfo, PeerInfo, ServiceInfo};
+use crate::{ConnectInfo, CreateInfo, DataInfo, HelloInfo, LocateInfo, NsRegisterInfo, PeerInfo};
+use crate::{PublishInfo, QueryInfo, RegisterInfo, ReplicaInfo, ResponseKind, ServiceInfo};
+use crate::{NsMatch, NsSearchInfo, Qos, ServiceKeys, StatusInfo, SubscriptionInfo};
+use crate::{AddressInfo, SubscriptionKind, SyncInfo};
 use dsf_core::base::Body;
 
 #[cfg(nope)]
@@ -16,13 +20,13 @@ impl Display for PeerInfo {
         if f.sign_plus() {
             write!(f, "id: {}", self.id)?;
         } else {
-            write!(f, ", {}", self.id)?;
+            write!(f, "{}", self.id)?;
         }
 
         if f.sign_plus() {
             write!(f, "\n  - address: {}", SocketAddr::from(*self.address()))?;
         } else {
-            write!(f, "{}", SocketAddr::from(*self.address()))?;
+            write!(f, ", {}", SocketAddr::from(*self.address()))?;
         }
 
         if f.sign_plus() {
@@ -63,7 +67,7 @@ impl Display for DataInfo {
         if f.sign_plus() {
             write!(f, "\n  - service id: {}", self.service)?;
         } else {
-            write!(f, "{}", self.service)?;
+            write!(f, ", {}", self.service)?;
         }
 
         let body = match &self.body {
@@ -75,7 +79,7 @@ impl Display for DataInfo {
         if f.sign_plus() {
             write!(f, "\n  - body: {}", body)?;
         } else {
-            write!(f, "{}", body)?;
+            write!(f, ", {}", body)?;
         }
 
         let parent = match &self.previous {
@@ -86,13 +90,13 @@ impl Display for DataInfo {
         if f.sign_plus() {
             write!(f, "\n  - parent: {}", parent)?;
         } else {
-            write!(f, "{}", parent)?;
+            write!(f, ", {}", parent)?;
         }
 
         if f.sign_plus() {
             write!(f, "\n  - signature: {}", self.signature)?;
         } else {
-            write!(f, "{}", self.signature)?;
+            write!(f, ", {}", self.signature)?;
         }
 
         Ok(())
@@ -110,7 +114,7 @@ impl Display for ServiceInfo {
         if f.sign_plus() {
             write!(f, "\n  - index: {}", self.index)?;
         } else {
-            write!(f, "{}", self.index)?;
+            write!(f, ", {}", self.index)?;
         }
 
         if f.sign_plus() {
@@ -125,6 +129,49 @@ impl Display for ServiceInfo {
             write!(f, ", {}", self.public_key)?;
         }
 
+        if let Some(updated) = self.last_updated {
+            let dt: chrono::DateTime<chrono::Local> = chrono::DateTime::from(updated);
+            let ht = chrono_humanize::HumanTime::from(dt);
+
+            if f.sign_plus() {
+                write!(f, "\n  - last updated: {}", ht)?;
+            } else {
+                write!(f, ", {}", ht)?;
+            }
+        }
+
+        if f.sign_plus() {
+            write!(f, "\n  - replicas: {}", self.replicas)?;
+        } else {
+            write!(f, ", {}", self.replicas)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for ServiceKeys {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "id: {}", self.id)?;
+        } else {
+            write!(f, "{}", self.id)?;
+        }
+
+        if f.sign_plus() {
+            write!(f, "\n  - public key: {}", self.public_key)?;
+        } else {
+            write!(f, ", {}", self.public_key)?;
+        }
+
+        if let Some(pk) = &self.private_key {
+            if f.sign_plus() {
+                write!(f, "\n  - private key: {}", pk.to_string().dimmed())?;
+            } else {
+                write!(f, ", {}", pk)?;
+            }
+        }
+
         if let Some(sk) = &self.secret_key {
             if f.sign_plus() {
                 write!(f, "\n  - secret key: {}", sk.to_string().dimmed())?;
@@ -133,23 +180,909 @@ impl Display for ServiceInfo {
             }
         }
 
-        if let Some(updated) = self.last_updated {
-            let dt: chrono::DateTime<chrono::Local> = chrono::DateTime::from(updated);
-            let ht = chrono_humanize::HumanTime::from(dt);
+        Ok(())
+    }
+}
+
+/// Format a timestamp relative to the current time
+fn human(t: SystemTime) -> chrono_humanize::HumanTime {
+    let dt: chrono::DateTime<chrono::Local> = chrono::DateTime::from(t);
+    chrono_humanize::HumanTime::from(dt)
+}
 
+impl Display for ReplicaInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "service id: {}", self.service_id)?;
+            write!(f, "\n  - page id: {}", self.page_id)?;
+        } else {
+            write!(f, "{}", self.service_id)?;
+        }
+
+        if f.sign_plus() {
+            write!(f, "\n  - peer id: {}", self.peer_id)?;
+        } else {
+            write!(f, ", {}", self.peer_id)?;
+        }
+
+        if f.sign_plus() {
+            write!(f, "\n  - version: {}", self.version)?;
+        } else {
+            write!(f, ", {}", self.version)?;
+        }
+
+        if f.sign_plus() {
+            write!(f, "\n  - issued: {}", human(self.issued))?;
+            write!(f, "\n  - updated: {}", human(self.updated))?;
+        } else {
+            write!(f, ", {}", human(self.updated))?;
+        }
+
+        if let Some(expiry) = self.expiry {
             if f.sign_plus() {
-                write!(f, "\n  - last updated: {}", ht)?;
+                write!(f, "\n  - expiry: {}", human(expiry))?;
             } else {
-                write!(f, ", {}", ht)?;
+                write!(f, ", {}", human(expiry))?;
             }
         }
 
+        let active = match self.active {
+            true => "active".green(),
+            false => "inactive".red(),
+        };
+
         if f.sign_plus() {
-            write!(f, "\n  - replicas: {}", self.replicas)?;
+            write!(f, "\n  - state: {}", active)?;
         } else {
-            write!(f, ", {}", self.replicas)?;
+            write!(f, ", {}", active)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for SubscriptionKind {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            SubscriptionKind::Peer(id) => write!(f, "peer {}", id),
+            SubscriptionKind::Socket(s) => write!(f, "socket {}", s),
+            SubscriptionKind::None => write!(f, "none"),
+        }
+    }
+}
+
+impl Display for SubscriptionInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "service id: {}", self.service_id)?;
+        } else {
+            write!(f, "{}", self.service_id)?;
+        }
+
+        if f.sign_plus() {
+            write!(f, "\n  - kind: {}", self.kind)?;
+        } else {
+            write!(f, ", {}", self.kind)?;
+        }
+
+        if let Some(updated) = self.updated {
+            if f.sign_plus() {
+                write!(f, "\n  - updated: {}", human(updated))?;
+            } else {
+                write!(f, ", {}", human(updated))?;
+            }
+        }
+
+        if let Some(expiry) = self.expiry {
+            if f.sign_plus() {
+                write!(f, "\n  - expiry: {}", human(expiry))?;
+            } else {
+                write!(f, ", {}", human(expiry))?;
+            }
+        }
+
+        if f.sign_plus() {
+            write!(f, "\n  - qos: {}", self.qos)?;
         }
 
         Ok(())
     }
 }
+
+impl Display for Qos {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{}, {}", self.priority, self.delivery)?;
+
+        if let Some(r) = self.rate_limit {
+            write!(f, ", {}/s", r)?;
+        }
+
+        if let Some(w) = self.batch_window {
+            write!(f, ", batch {}", humantime::format_duration(w))?;
+        }
+
+        if let Some(b) = self.max_backlog {
+            write!(f, ", backlog {}", b)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for StatusInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "id: {}", self.id)?;
+            write!(f, "\n  - peers: {}", self.peers)?;
+            write!(f, "\n  - services: {}", self.services)?;
+        } else {
+            write!(
+                f,
+                "{}, {} peers, {} services",
+                self.id, self.peers, self.services
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for ConnectInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "id: {}", self.id)?;
+            write!(f, "\n  - peers: {}", self.peers)?;
+        } else {
+            write!(f, "{}, {} peers", self.id, self.peers)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for RegisterInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "page version: {}", self.page_version)?;
+        } else {
+            write!(f, "{}", self.page_version)?;
+        }
+
+        if let Some(v) = self.replica_version {
+            if f.sign_plus() {
+                write!(f, "\n  - replica version: {}", v)?;
+            } else {
+                write!(f, ", {}", v)?;
+            }
+        }
+
+        if f.sign_plus() {
+            write!(f, "\n  - peers: {}", self.peers)?;
+        } else {
+            write!(f, ", {} peers", self.peers)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for LocateInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "page version: {}", self.page_version)?;
+            write!(f, "\n  - origin: {}", self.origin)?;
+            write!(f, "\n  - updated: {}", self.updated)?;
+        } else {
+            write!(f, "{}", self.page_version)?;
+
+            if self.origin {
+                write!(f, ", origin")?;
+            }
+            if self.updated {
+                write!(f, ", updated")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for PublishInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "index: {}", self.index)
+        } else {
+            write!(f, "{}", self.index)
+        }
+    }
+}
+
+impl Display for NsRegisterInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "ns: {}", self.ns)?;
+        } else {
+            write!(f, "{}", self.ns)?;
+        }
+
+        if let Some(p) = &self.prefix {
+            if f.sign_plus() {
+                write!(f, "\n  - prefix: {}", p)?;
+            } else {
+                write!(f, ", {}", p)?;
+            }
+        }
+
+        if let Some(t) = &self.target {
+            if f.sign_plus() {
+                write!(f, "\n  - target: {}", t)?;
+            } else {
+                write!(f, ", {}", t)?;
+            }
+        }
+
+        if let Some(n) = &self.name {
+            if f.sign_plus() {
+                write!(f, "\n  - name: {}", n)?;
+            } else {
+                write!(f, ", {}", n)?;
+            }
+        }
+
+        for h in &self.hashes {
+            if f.sign_plus() {
+                write!(f, "\n  - hash: {}", h)?;
+            } else {
+                write!(f, ", {}", h)?;
+            }
+        }
+
+        if let Some(expiry) = self.expiry {
+            if f.sign_plus() {
+                write!(f, "\n  - expiry: {}", human(expiry))?;
+            } else {
+                write!(f, ", {}", human(expiry))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for AddressInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "address: {}", self.address)?;
+            write!(f, "\n  - kind: {}", self.kind)?;
+        } else {
+            write!(f, "{}, {}", self.address, self.kind)?;
+        }
+
+        if self.preferred {
+            if f.sign_plus() {
+                write!(f, "\n  - preferred: {}", "true".green())?;
+            } else {
+                write!(f, ", {}", "preferred".green())?;
+            }
+        }
+
+        if let Some(observed) = self.observed {
+            if f.sign_plus() {
+                write!(f, "\n  - observed: {}", human(observed))?;
+            } else {
+                write!(f, ", {}", human(observed))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for NsMatch {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "target: {}", self.target)?;
+        } else {
+            write!(f, "{}", self.target)?;
+        }
+
+        if let Some(n) = &self.name {
+            if f.sign_plus() {
+                write!(f, "\n  - name: {}", n)?;
+            } else {
+                write!(f, ", {}", n)?;
+            }
+        }
+
+        if f.sign_plus() {
+            write!(f, "\n  - hash: {}", self.hash)?;
+            write!(f, "\n  - page: {}", self.page)?;
+        }
+
+        if let Some(expiry) = self.expiry {
+            if f.sign_plus() {
+                write!(f, "\n  - expiry: {}", human(expiry))?;
+            } else {
+                write!(f, ", {}", human(expiry))?;
+            }
+        }
+
+        if let Some(i) = &self.info {
+            if f.sign_plus() {
+                write!(f, "\n  - state: {}", i.state)?;
+            } else {
+                write!(f, ", {}", i.state)?;
+            }
+        }
+
+        if let Some(l) = &self.located {
+            if f.sign_plus() {
+                write!(f, "\n  - located: version {}", l.page_version)?;
+            } else {
+                write!(f, ", {}", "located".green())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for NsSearchInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "ns: {} ({} matches)", self.ns, self.matches.len())?;
+        } else {
+            write!(f, "{}, {} matches", self.ns, self.matches.len())?;
+        }
+
+        for m in &self.matches {
+            writeln!(f)?;
+            display_item(f, m)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for CreateInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "id: {}", self.id)?;
+        } else {
+            write!(f, "{}", self.id)?;
+        }
+
+        // Key material is only displayed via an explicit `service export-keys`
+        if self.secret_key.is_some() && f.sign_plus() {
+            write!(f, "\n  - secret key: {}", "redacted".dimmed())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for HelloInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let caps: Vec<_> = self.capabilities.iter().map(|c| c.to_string()).collect();
+
+        if f.sign_plus() {
+            write!(f, "protocol: {}", self.protocol)?;
+            write!(f, "\n  - version: {}", self.version)?;
+            write!(f, "\n  - capabilities: {}", caps.join(", "))?;
+        } else {
+            write!(f, "v{}, {}", self.protocol, self.version)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for QueryInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        for d in &self.data {
+            display_item(f, d)?;
+            writeln!(f)?;
+        }
+
+        write!(f, "total: {}", self.total)
+    }
+}
+
+impl Display for SyncInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if f.sign_plus() {
+            write!(f, "service id: {}", self.service)?;
+            write!(f, "\n  - fetched: {}", self.fetched)?;
+        } else {
+            write!(f, "{}, {} fetched", self.service, self.fetched)?;
+        }
+
+        if let Some(latest) = self.latest {
+            if f.sign_plus() {
+                write!(f, "\n  - latest: {}", latest)?;
+            } else {
+                write!(f, ", latest {}", latest)?;
+            }
+        }
+
+        if f.sign_plus() {
+            for g in &self.gaps {
+                write!(f, "\n  - gap: {} (missing {})", g.index, g.missing)?;
+            }
+            write!(f, "\n  - peers: {}", self.peers.len())?;
+        } else if !self.gaps.is_empty() {
+            write!(f, ", {}", format!("{} gaps", self.gaps.len()).red())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write an item, propagating the verbose (`{:+}`) flag
+fn display_item<T: Display>(f: &mut Formatter, v: &T) -> Result {
+    if f.sign_plus() {
+        write!(f, "{:+}", v)
+    } else {
+        write!(f, "{}", v)
+    }
+}
+
+/// Write a list of items, one per line
+fn display_list<'a, T: Display + 'a>(
+    f: &mut Formatter,
+    items: impl IntoIterator<Item = &'a T>,
+) -> Result {
+    for (i, v) in items.into_iter().enumerate() {
+        if i > 0 {
+            writeln!(f)?;
+        }
+        display_item(f, v)?;
+    }
+
+    Ok(())
+}
+
+impl Display for ResponseKind {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            ResponseKind::None => Ok(()),
+            ResponseKind::Hello(i) => display_item(f, i),
+            ResponseKind::VersionMismatch(i) => display_item(f, i),
+            ResponseKind::Status(i) => display_item(f, i),
+            ResponseKind::Connected(i) => display_item(f, i),
+            ResponseKind::Peer(i) => display_item(f, i),
+            ResponseKind::Peers(p) => display_list(f, p.iter().map(|(_, i)| i)),
+            ResponseKind::Service(i) => display_item(f, i),
+            ResponseKind::Services(p) => display_list(f, p.iter()),
+            ResponseKind::Keys(i) => display_item(f, i),
+            ResponseKind::Registered(i) => display_item(f, i),
+            ResponseKind::Located(i) => display_item(f, i),
+            ResponseKind::Subscribed(s) => display_list(f, s.iter()),
+            ResponseKind::Subscriber(i) => display_item(f, i),
+            ResponseKind::Subscribers(p) => display_list(f, p.iter()),
+            ResponseKind::Replicas(r) => display_list(f, r.iter()),
+            ResponseKind::Published(i) => display_item(f, i),
+            ResponseKind::Addresses(a) => display_list(f, a.iter()),
+            ResponseKind::Datastore(d) => {
+                for (i, (id, pages)) in d.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}, {} pages", id, pages.len())?;
+                }
+                Ok(())
+            }
+            ResponseKind::Ns(i) => display_item(f, i),
+            ResponseKind::NsSearch(i) => display_item(f, i),
+            ResponseKind::NsRegistrations(p) => display_list(f, p.iter()),
+            ResponseKind::Data(p) => display_list(f, p.iter()),
+            ResponseKind::Query(i) => display_item(f, i),
+            ResponseKind::Synced(i) => display_item(f, i),
+            ResponseKind::Pages(p) => {
+                for (i, c) in p.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}, {}, {}", c.id(), c.header().index(), c.signature())?;
+                }
+                Ok(())
+            }
+            ResponseKind::Page(c) => {
+                write!(f, "{}, {}, {}", c.id(), c.header().index(), c.signature())
+            }
+            ResponseKind::StreamOpen(i) => display_item(f, i),
+            ResponseKind::StreamItem(i) => display_item(f, i),
+            ResponseKind::StreamEnd => write!(f, "stream ended"),
+            ResponseKind::StreamCancelled => write!(f, "stream cancelled"),
+            ResponseKind::Unrecognised => write!(f, "{}", "unrecognised request".red()),
+            ResponseKind::Unauthorized => write!(f, "{}", "unauthorized".red()),
+            ResponseKind::Error(e) => write!(f, "{}", format!("error: {:?}", e).red()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use dsf_core::types::*;
+
+    use super::*;
+    use crate::*;
+
+    /// Disable colours so rendered output is comparable
+    fn plain() {
+        colored::control::set_override(false);
+    }
+
+    /// Render an object in both compact and verbose (`{:+}`) forms
+    fn render<T: Display>(v: &T) -> (String, String) {
+        plain();
+        (format!("{}", v), format!("{:+}", v))
+    }
+
+    fn id(v: u8) -> Id {
+        Id::from([v; 32])
+    }
+
+    fn sig(v: u8) -> Signature {
+        Signature::from([v; 64])
+    }
+
+    #[test]
+    fn display_replica_info() {
+        let now = SystemTime::now();
+        let r = ReplicaInfo {
+            service_id: id(1),
+            page_id: id(2),
+            peer_id: id(3),
+            version: 4,
+            issued: now,
+            updated: now,
+            expiry: None,
+            active: true,
+        };
+
+        let (a, b) = (id(1), id(3));
+        assert_eq!(
+            render(&r),
+            (
+                format!("{}, {}, 4, now, active", a, b),
+                format!(
+                    "service id: {}\n  - page id: {}\n  - peer id: {}\n  - version: 4\n  - issued: now\n  - updated: now\n  - state: active",
+                    a, id(2), b
+                ),
+            )
+        );
+    }
+
+    #[test]
+    fn display_subscription_info() {
+        let s = SubscriptionInfo {
+            service_id: id(1),
+            kind: SubscriptionKind::Socket(7),
+            updated: None,
+            expiry: None,
+            qos: Qos {
+                priority: QosPriority::Latency,
+                delivery: Delivery::AtLeastOnce,
+                rate_limit: Some(5),
+                batch_window: Some(Duration::from_secs(2)),
+                max_backlog: Some(100),
+            },
+        };
+
+        assert_eq!(
+            render(&s),
+            (
+                format!("{}, socket 7", id(1)),
+                format!(
+                    "service id: {}\n  - kind: socket 7\n  - qos: latency, at_least_once, 5/s, batch 2s, backlog 100",
+                    id(1)
+                ),
+            )
+        );
+
+        assert_eq!(
+            render(&SubscriptionKind::Peer(id(2))).0,
+            format!("peer {}", id(2))
+        );
+        assert_eq!(render(&SubscriptionKind::None).0, "none");
+        assert_eq!(render(&Qos::default()).0, "none, best_effort");
+    }
+
+    #[test]
+    fn display_status_info() {
+        let i = StatusInfo {
+            id: id(1),
+            peers: 2,
+            services: 3,
+        };
+
+        assert_eq!(
+            render(&i),
+            (
+                format!("{}, 2 peers, 3 services", id(1)),
+                format!("id: {}\n  - peers: 2\n  - services: 3", id(1)),
+            )
+        );
+    }
+
+    #[test]
+    fn display_connect_info() {
+        let i = ConnectInfo {
+            id: id(1),
+            peers: 2,
+        };
+
+        assert_eq!(
+            render(&i),
+            (
+                format!("{}, 2 peers", id(1)),
+                format!("id: {}\n  - peers: 2", id(1)),
+            )
+        );
+    }
+
+    #[test]
+    fn display_register_info() {
+        let i = RegisterInfo {
+            page_version: 1,
+            replica_version: Some(2),
+            peers: 3,
+        };
+
+        assert_eq!(
+            render(&i),
+            (
+                "1, 2, 3 peers".to_string(),
+                "page version: 1\n  - replica version: 2\n  - peers: 3".to_string(),
+            )
+        );
+    }
+
+    #[test]
+    fn display_locate_info() {
+        let i = LocateInfo {
+            origin: true,
+            updated: false,
+            page_version: 4,
+            page: None,
+        };
+
+        assert_eq!(
+            render(&i),
+            (
+                "4, origin".to_string(),
+                "page version: 4\n  - origin: true\n  - updated: false".to_string(),
+            )
+        );
+    }
+
+    #[test]
+    fn display_publish_info() {
+        let i = PublishInfo { index: 3 };
+
+        assert_eq!(render(&i), ("3".to_string(), "index: 3".to_string()));
+    }
+
+    #[test]
+    fn display_ns_register_info() {
+        let h = CryptoHash::from([4u8; 32]);
+        let i = NsRegisterInfo {
+            ns: id(1),
+            prefix: Some("app".to_string()),
+            target: Some(id(2)),
+            name: Some("sensor".to_string()),
+            hashes: vec![h.clone()],
+            expiry: None,
+        };
+
+        assert_eq!(
+            render(&i),
+            (
+                format!("{}, app, {}, sensor, {}", id(1), id(2), h),
+                format!(
+                    "ns: {}\n  - prefix: app\n  - target: {}\n  - name: sensor\n  - hash: {}",
+                    id(1),
+                    id(2),
+                    h
+                ),
+            )
+        );
+    }
+
+    #[test]
+    fn display_ns_search_info() {
+        let h = CryptoHash::from([4u8; 32]);
+        let m = NsMatch {
+            target: id(2),
+            name: Some("sensor".to_string()),
+            hash: h.clone(),
+            page: sig(5),
+            expiry: None,
+            info: None,
+            located: Some(LocateInfo {
+                origin: false,
+                updated: true,
+                page_version: 3,
+                page: None,
+            }),
+        };
+
+        assert_eq!(
+            render(&m),
+            (
+                format!("{}, sensor, located", id(2)),
+                format!(
+                    "target: {}\n  - name: sensor\n  - hash: {}\n  - page: {}\n  - located: version 3",
+                    id(2),
+                    h,
+                    sig(5)
+                ),
+            )
+        );
+
+        let i = NsSearchInfo {
+            ns: id(1),
+            matches: vec![m],
+            next_cursor: None,
+        };
+        let (compact, verbose) = render(&i);
+
+        assert_eq!(
+            compact,
+            format!("{}, 1 matches\n{}, sensor, located", id(1), id(2))
+        );
+        assert!(verbose.starts_with(&format!("ns: {} (1 matches)\ntarget: {}", id(1), id(2))));
+    }
+
+    #[test]
+    fn display_create_info_redacts_keys() {
+        let i = CreateInfo {
+            id: id(1),
+            secret_key: Some(SecretKey::from([9u8; 32])),
+        };
+        let key = SecretKey::from([9u8; 32]).to_string();
+
+        let (compact, verbose) = render(&i);
+        assert_eq!(compact, format!("{}", id(1)));
+        assert_eq!(verbose, format!("id: {}\n  - secret key: redacted", id(1)));
+        assert!(!compact.contains(&key) && !verbose.contains(&key));
+    }
+
+    #[test]
+    fn display_hello_info() {
+        let i = HelloInfo {
+            protocol: 1,
+            version: "0.2.0".to_string(),
+            capabilities: vec![Capability::Status, Capability::Stream],
+        };
+
+        assert_eq!(
+            render(&i),
+            (
+                "v1, 0.2.0".to_string(),
+                "protocol: 1\n  - version: 0.2.0\n  - capabilities: status, stream".to_string(),
+            )
+        );
+    }
+
+    #[test]
+    fn display_query_info() {
+        let d = DataInfo {
+            service: id(1),
+            index: 2,
+            body: Body::None,
+            previous: None,
+            signature: sig(3),
+        };
+        let i = QueryInfo {
+            data: vec![d],
+            total: 1,
+            next_cursor: None,
+        };
+
+        assert_eq!(
+            render(&i).0,
+            format!("2, {}, None, None, {}\ntotal: 1", id(1), sig(3))
+        );
+    }
+
+    #[test]
+    fn display_sync_info() {
+        let i = SyncInfo {
+            service: id(1),
+            fetched: 4,
+            latest: Some(6),
+            gaps: vec![SyncGap {
+                index: 2,
+                missing: sig(3),
+            }],
+            peers: vec![id(2)],
+        };
+
+        assert_eq!(
+            render(&i),
+            (
+                format!("{}, 4 fetched, latest 6, 1 gaps", id(1)),
+                format!(
+                    "service id: {}\n  - fetched: 4\n  - latest: 6\n  - gap: 2 (missing {})\n  - peers: 1",
+                    id(1),
+                    sig(3)
+                ),
+            )
+        );
+    }
+
+    #[test]
+    fn display_address_info() {
+        let i = AddressInfo {
+            address: "10.0.0.1:10100".parse().unwrap(),
+            kind: AddressKind::Explicit,
+            preferred: true,
+            observed: None,
+        };
+
+        assert_eq!(
+            render(&i),
+            (
+                "10.0.0.1:10100, explicit, preferred".to_string(),
+                "address: 10.0.0.1:10100\n  - kind: explicit\n  - preferred: true".to_string(),
+            )
+        );
+    }
+
+    #[test]
+    fn display_response_kind() {
+        let status = StatusInfo {
+            id: id(1),
+            peers: 2,
+            services: 3,
+        };
+        assert_eq!(
+            render(&ResponseKind::Status(status.clone())),
+            render(&status)
+        );
+
+        let addresses: Vec<_> = ["10.0.0.1:1", "10.0.0.2:2"]
+            .iter()
+            .map(|a| AddressInfo {
+                address: a.parse().unwrap(),
+                kind: AddressKind::Bound,
+                preferred: false,
+                observed: None,
+            })
+            .collect();
+        assert_eq!(
+            render(&ResponseKind::Addresses(addresses)),
+            (
+                "10.0.0.1:1, bound\n10.0.0.2:2, bound".to_string(),
+                "address: 10.0.0.1:1\n  - kind: bound\naddress: 10.0.0.2:2\n  - kind: bound"
+                    .to_string(),
+            )
+        );
+        assert_eq!(
+            render(&ResponseKind::Published(PublishInfo { index: 2 })),
+            ("2".to_string(), "index: 2".to_string())
+        );
+
+        assert_eq!(
+            render(&ResponseKind::Datastore(vec![(
+                id(1),
+                vec![vec![], vec![]]
+            )]))
+            .0,
+            format!("{}, 2 pages", id(1))
+        );
+
+        assert_eq!(render(&ResponseKind::None).0, "");
+        assert_eq!(render(&ResponseKind::StreamEnd).0, "stream ended");
+        assert_eq!(render(&ResponseKind::StreamCancelled).0, "stream cancelled");
+        assert_eq!(
+            render(&ResponseKind::Unrecognised).0,
+            "unrecognised request"
+        );
+        assert_eq!(render(&ResponseKind::Unauthorized).0, "unauthorized");
+    }
+}
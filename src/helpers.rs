@@ -6,6 +6,8 @@ use std::time::Duration;
 
 use humantime::Duration as HumanDuration;
 
+use dsf_core::wire::Container;
+
 pub fn try_parse_sock_addr(from: &str) -> Result<SocketAddr, IoError> {
     let mut addrs = from.to_socket_addrs()?;
 
@@ -43,3 +45,9 @@ pub fn parse_duration(s: &str) -> Result<Duration, humantime::DurationError> {
 pub fn data_from_str(s: &str) -> Vec<u8> {
     s.as_bytes().to_vec()
 }
+
+/// Parse a base64 encoded DSF container (page or data object)
+pub fn container_from_base64(s: &str) -> Result<Container, base64::DecodeError> {
+    let buff = base64::decode(s)?;
+    Ok(Container::from(buff))
+}
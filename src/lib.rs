@@ -1,3 +1,4 @@
+use std::pin::Pin;
 use std::time::SystemTime;
 
 extern crate chrono;
@@ -5,10 +6,14 @@ extern crate chrono_english;
 extern crate chrono_humanize;
 extern crate humantime;
 
+extern crate asynchronous_codec;
+extern crate bytes;
 extern crate futures;
 use dsf_core::api::ServiceHandle;
 use dsf_core::wire::Container;
 use futures::prelude::*;
+extern crate async_trait;
+use async_trait::async_trait;
 extern crate structopt;
 use structopt::StructOpt;
 
@@ -20,12 +25,19 @@ extern crate strum_macros;
 extern crate rand;
 use rand::random;
 
+extern crate base64;
+extern crate hmac;
+extern crate sha2;
+
 extern crate dsf_core;
 use dsf_core::error::Error;
 use dsf_core::types::*;
 
 #[macro_use]
 extern crate serde;
+extern crate serde_json;
+extern crate serde_yaml;
+extern crate terminal_size;
 
 extern crate colored;
 
@@ -56,15 +68,138 @@ pub use page::*;
 pub mod name;
 pub use name::*;
 
+pub mod delegation;
+pub use delegation::*;
+
+pub mod response;
+pub use response::*;
+
+pub mod stream;
+pub use stream::*;
+
+pub mod paging;
+pub use paging::*;
+
+pub mod protocol;
+pub use protocol::*;
+
+pub mod auth;
+pub use auth::*;
+
+pub mod policy;
+pub use policy::*;
+
+pub mod output;
+pub use output::*;
+
+pub mod table;
+pub use table::*;
+
 pub mod display;
 
+pub mod codec;
+
+#[cfg(all(feature = "client", unix))]
+pub mod client;
+
 mod helpers;
 
 /// API trait implements RPC API for the daemon (or delegation)
+#[async_trait]
 pub trait Rpc {
+    type Error: From<UnexpectedResponse> + Send;
+
+    /// Execute a request, returning the matching response
+    async fn exec(&mut self, req: Request) -> Result<Response, Self::Error>;
+
+    /// Exchange protocol version and capabilities with the daemon.
+    ///
+    /// Daemons with an incompatible protocol version respond with `VersionMismatch`,
+    /// daemons predating the handshake respond with `Unrecognised`.
+    async fn hello(&mut self) -> Result<HelloInfo, Self::Error> {
+        let req = Request::new(RequestKind::Hello(HelloInfo::default()));
+        Ok(self.exec(req).await?.into_hello()?)
+    }
+
+    /// Fetch the status of the daemon
+    async fn status(&mut self) -> Result<StatusInfo, Self::Error> {
+        Ok(self
+            .exec(Request::new(RequestKind::Status))
+            .await?
+            .into_status()?)
+    }
+
+    /// List peers known by the daemon
+    async fn peer_list(
+        &mut self,
+        options: PeerOptions,
+    ) -> Result<Paged<(Id, PeerInfo)>, Self::Error> {
+        let req = Request::new(RequestKind::Peer(PeerCommands::List(options)));
+
+        Ok(self.exec(req).await?.into_peers()?)
+    }
+
+    /// List services known by the daemon
+    async fn service_list(
+        &mut self,
+        options: service::ListOptions,
+    ) -> Result<Paged<ServiceInfo>, Self::Error> {
+        let req = Request::new(RequestKind::Service(ServiceCommands::List(options)));
+
+        Ok(self.exec(req).await?.into_services()?)
+    }
+
+    /// Search for services using a name service
+    async fn ns_search(&mut self, options: NsSearchOptions) -> Result<NsSearchInfo, Self::Error> {
+        let req = Request::new(RequestKind::Ns(NsCommands::Search(options)));
+
+        Ok(self.exec(req).await?.into_ns_search()?)
+    }
+
+    /// Create a new service
+    async fn service_create(&mut self, options: CreateOptions) -> Result<ServiceInfo, Self::Error> {
+        let req = Request::new(RequestKind::Service(ServiceCommands::Create(options)));
+
+        Ok(self.exec(req).await?.into_service()?)
+    }
+
+    /// Export key material for a service
+    async fn service_export_keys(
+        &mut self,
+        service: ServiceIdentifier,
+    ) -> Result<ServiceKeys, Self::Error> {
+        let req = Request::new(RequestKind::Service(ServiceCommands::ExportKeys(
+            service.into(),
+        )));
+
+        Ok(self.exec(req).await?.into_keys()?)
+    }
+}
+
+/// Legacy RPC trait returning boxed futures.
+///
+/// Implementers of this trait are provided with [`Rpc`] via a blanket implementation,
+/// allowing existing implementations to be migrated incrementally.
+pub trait LegacyRpc {
     type Error;
 
-    fn exec(&mut self, req: Request) -> Box<dyn Future<Output = Result<Response, Self::Error>>>;
+    fn exec(
+        &mut self,
+        req: Request,
+    ) -> Box<dyn Future<Output = Result<Response, Self::Error>> + Send>;
+}
+
+#[async_trait]
+impl<T> Rpc for T
+where
+    T: LegacyRpc + Send,
+    <T as LegacyRpc>::Error: From<UnexpectedResponse> + Send,
+{
+    type Error = <T as LegacyRpc>::Error;
+
+    async fn exec(&mut self, req: Request) -> Result<Response, Self::Error> {
+        Pin::from(LegacyRpc::exec(self, req)).await
+    }
 }
 
 /// RPC Request container for requests from a client to the daemon
@@ -72,6 +207,8 @@ pub trait Rpc {
 pub struct Request {
     req_id: u64,
     kind: RequestKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth: Option<Auth>,
 }
 
 impl Request {
@@ -79,6 +216,7 @@ impl Request {
         Self {
             req_id: random(),
             kind,
+            auth: None,
         }
     }
 
@@ -137,9 +275,14 @@ pub struct PageBounds {
     /// Maximum number of responses to return
     pub count: Option<usize>,
 
-    #[structopt(long = "offset")]
-    /// Offset of returned results
+    #[structopt(long = "offset", conflicts_with = "cursor")]
+    /// Offset of returned results (prefer cursors, offsets may skip or duplicate results)
     pub offset: Option<usize>,
+
+    #[structopt(long = "cursor")]
+    #[serde(default)]
+    /// Return results following the provided cursor
+    pub cursor: Option<Cursor>,
 }
 
 impl Default for PageBounds {
@@ -147,6 +290,18 @@ impl Default for PageBounds {
         Self{
             count: None,
             offset: None,
+            cursor: None,
+        }
+    }
+}
+
+impl PageBounds {
+    /// Create bounds for the page following the provided cursor
+    pub fn after(&self, cursor: Cursor) -> PageBounds {
+        PageBounds {
+            count: self.count,
+            offset: None,
+            cursor: Some(cursor),
         }
     }
 }
@@ -175,6 +330,10 @@ impl Default for TimeBounds {
 /// Specific request kinds for issuing requests to the daemon from the client
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
 pub enum RequestKind {
+    #[structopt(name = "hello")]
+    /// Exchange protocol version and capabilities with the daemon
+    Hello(HelloInfo),
+
     #[structopt(name = "status")]
     /// Checks the status of the DSF daemon
     Status,
@@ -203,6 +362,14 @@ pub enum RequestKind {
     /// Subcommand for managing subscribers
     Subscriber(SubscriberCommands),
 
+    #[structopt(name = "replica")]
+    /// Subcommand for managing service replicas
+    Replica(ReplicaCommands),
+
+    #[structopt(name = "delegate")]
+    /// Subcommand for delegating operations from constrained devices
+    Delegate(DelegationCommands),
+
     #[structopt(name = "config")]
     /// Subcommand for managing runtime daemon configuration
     Config(ConfigCommands),
@@ -215,6 +382,13 @@ pub enum RequestKind {
     #[structopt(name = "stream")]
     Stream(SubscribeOptions),
 
+    /// Cancel an open data stream
+    #[structopt(name = "stream-cancel")]
+    StreamCancel(StreamCancelOptions),
+
+    /// Acknowledge data received on an open stream
+    #[structopt(name = "stream-ack")]
+    StreamAck(StreamAckOptions),
 }
 
 /// Response container for replies from the daemon to the client
@@ -239,38 +413,65 @@ impl Response {
 }
 
 /// Specific response kinds for processing responses from the daemon
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, IntoStaticStr)]
 pub enum ResponseKind {
     None,
 
+    Hello(HelloInfo),
+    VersionMismatch(VersionMismatch),
+
     Status(StatusInfo),
     Connected(ConnectInfo),
 
     Peer(PeerInfo),
-    Peers(Vec<(Id, PeerInfo)>),
+    Peers(Paged<(Id, PeerInfo)>),
 
     Service(ServiceInfo),
-    Services(Vec<ServiceInfo>),
+    Services(Paged<ServiceInfo>),
+    Keys(ServiceKeys),
     Registered(RegisterInfo),
     Located(LocateInfo),
 
     Subscribed(Vec<SubscriptionInfo>),
+    Subscriber(SubscriptionInfo),
+    Subscribers(Paged<SubscriptionInfo>),
+
+    Replicas(Vec<ReplicaInfo>),
 
     Published(PublishInfo),
 
     Datastore(Vec<(Id, Vec<Vec<u8>>)>),
 
+    /// Daemon addresses, returned by all address configuration commands
+    Addresses(Vec<AddressInfo>),
+
     Ns(NsRegisterInfo),
+    NsSearch(NsSearchInfo),
+    NsRegistrations(Paged<NsRegisterInfo>),
 
-    Data(Vec<DataInfo>),
+    Data(Paged<DataInfo>),
+    Query(QueryInfo),
+    Synced(SyncInfo),
 
     Pages(Vec<Container>),
 
     Page(Container),
 
+    /// Stream opened, first response to a `RequestKind::Stream` request
+    StreamOpen(SubscriptionInfo),
+    /// Data object published to an open stream
+    StreamItem(DataInfo),
+    /// Stream closed by the daemon
+    StreamEnd,
+    /// Stream closed following a `RequestKind::StreamCancel` request
+    StreamCancelled,
+
     //Value(String),
     Unrecognised,
 
+    /// Request authentication failed, or the principal is not permitted to execute the request
+    Unauthorized,
+
     Error(Error),
 }
 
@@ -1,11 +1,14 @@
+use std::fmt;
+use std::str::FromStr;
 use std::time::SystemTime;
 
 use structopt::StructOpt;
+use strum::VariantNames;
 use serde::{Serialize, Deserialize};
 
 use dsf_core::{prelude::*, types::CryptoHash};
 
-use crate::ServiceIdentifier;
+use crate::{Cursor, LocateInfo, PageBounds, ServiceIdentifier, ServiceInfo};
 
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
@@ -15,8 +18,28 @@ pub enum NsCommands {
     Search(NsSearchOptions),
     
     #[structopt()]
-    /// TODO: De-register an external address for the daemon
+    /// Register a service with the specified name service
     Register(NsRegisterOptions),
+
+    #[structopt()]
+    /// Create a new name service
+    Create(NsCreateOptions),
+
+    #[structopt()]
+    /// List registrations held by the specified name service
+    List(NsListOptions),
+
+    #[structopt()]
+    /// Remove a name / hash registration from the specified name service
+    Deregister(NsRegisterOptions),
+
+    #[structopt()]
+    /// Renew a name / hash registration, extending its expiry
+    Renew(NsRegisterOptions),
+
+    #[structopt()]
+    /// List all registrations for a target service
+    Reverse(NsReverseOptions),
 }
 
 
@@ -30,9 +53,126 @@ pub struct NsSearchOptions {
     /// Service for search operation
     pub name: Option<String>,
 
+    #[structopt(long = "name-prefix", group="filters")]
+    /// Match services with names starting with the provided prefix
+    pub name_prefix: Option<String>,
+
     #[structopt(long, group="filters")]
-    /// Hashes for searching
-    pub hash: Option<CryptoHash>,
+    /// Match services with names matching a glob pattern (`*` and `?` wildcards)
+    pub glob: Option<String>,
+
+    #[structopt(long)]
+    /// Hashes (or `key:value` tags) for searching, combined using `--match`
+    pub hash: Vec<NsHash>,
+
+    #[structopt(long = "match", default_value = "all", possible_values = HashMatch::VARIANTS)]
+    #[serde(default)]
+    /// Require all or any of the provided hashes to match
+    pub hash_match: HashMatch,
+
+    #[structopt(flatten)]
+    #[serde(default)]
+    pub page_bounds: PageBounds,
+}
+
+impl NsSearchOptions {
+    /// Compute the lookup hashes for this search (including an exact name where provided),
+    /// using the name service lookup derivation `hash` for names and tags
+    pub fn lookups<F, E>(&self, hash: F) -> Result<Vec<CryptoHash>, E>
+    where
+        F: Fn(&str) -> Result<CryptoHash, E>,
+    {
+        let mut hashes = Vec::with_capacity(self.hash.len() + 1);
+
+        if let Some(n) = &self.name {
+            hashes.push(hash(n)?);
+        }
+
+        for h in &self.hash {
+            hashes.push(h.resolve(&hash)?);
+        }
+
+        Ok(hashes)
+    }
+
+    /// Check whether a registered name matches the prefix or glob filters.
+    /// Exact names are matched by hash (see [`NsSearchOptions::lookups`])
+    pub fn matches_name(&self, name: Option<&str>) -> bool {
+        match (&self.name_prefix, &self.glob, name) {
+            (Some(p), _, Some(n)) => n.starts_with(p.as_str()),
+            (_, Some(g), Some(n)) => glob_match(g, n),
+            (None, None, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Check whether registered hashes match the provided lookups
+    pub fn matches_hashes(&self, lookups: &[CryptoHash], hashes: &[CryptoHash]) -> bool {
+        if lookups.is_empty() {
+            return true;
+        }
+
+        match self.hash_match {
+            HashMatch::All => lookups.iter().all(|l| hashes.contains(l)),
+            HashMatch::Any => lookups.iter().any(|l| hashes.contains(l)),
+        }
+    }
+}
+
+/// Combination of hashes for name service searches
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+    Default,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum HashMatch {
+    /// Match services registered with all provided hashes
+    #[default]
+    All,
+    /// Match services registered with any of the provided hashes
+    Any,
+}
+
+/// Match a value against a glob pattern, supporting `*` (any sequence) and `?` (any character)
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let v: Vec<char> = value.chars().collect();
+
+    let (mut pi, mut vi) = (0, 0);
+    // Position of the last `*` in the pattern, and the value position it matched from
+    let mut star: Option<(usize, usize)> = None;
+
+    while vi < v.len() {
+        match p.get(pi) {
+            Some('*') => {
+                star = Some((pi, vi));
+                pi += 1;
+            }
+            Some(c) if *c == '?' || *c == v[vi] => {
+                pi += 1;
+                vi += 1;
+            }
+            _ => match star {
+                // Backtrack, extending the sequence matched by the last `*`
+                Some((sp, sv)) => {
+                    pi = sp + 1;
+                    vi = sv + 1;
+                    star = Some((sp, sv + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    p[pi..].iter().all(|c| *c == '*')
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, StructOpt)]
@@ -49,15 +189,287 @@ pub struct NsRegisterOptions {
     pub name: Option<String>,
 
     #[structopt(long)]
-    /// Hashes to associate with this service
-    pub hash: Vec<CryptoHash>,
+    /// Hashes (or `key:value` tags) to associate with this service
+    pub hash: Vec<NsHash>,
+}
+
+impl NsRegisterOptions {
+    /// Compute the lookup hashes for this registration (including the name where provided),
+    /// using the name service lookup derivation `hash` for names and tags
+    pub fn lookups<F, E>(&self, hash: F) -> Result<Vec<CryptoHash>, E>
+    where
+        F: Fn(&str) -> Result<CryptoHash, E>,
+    {
+        let mut hashes = Vec::with_capacity(self.hash.len() + 1);
+
+        if let Some(n) = &self.name {
+            hashes.push(hash(n)?);
+        }
+
+        for h in &self.hash {
+            hashes.push(h.resolve(&hash)?);
+        }
+
+        Ok(hashes)
+    }
 }
 
+/// Hash used for name service lookups, either provided directly or derived from a tag.
+///
+/// Tags are sent to the daemon unresolved (serialised as `{"Tag": ..}`, with pre-computed
+/// hashes as `{"Hash": ..}`), and resolved using the name service lookup derivation in the
+/// same manner as names.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum NsHash {
+    /// Pre-computed lookup hash
+    Hash(CryptoHash),
+    /// `key:value` tag (for example `type:sensor`), resolved by the daemon
+    Tag(String),
+}
+
+impl NsHash {
+    /// Resolve the lookup hash, using the name service lookup derivation `hash` for tags
+    pub fn resolve<F, E>(&self, hash: F) -> Result<CryptoHash, E>
+    where
+        F: Fn(&str) -> Result<CryptoHash, E>,
+    {
+        match self {
+            NsHash::Hash(h) => Ok(h.clone()),
+            NsHash::Tag(t) => hash(t),
+        }
+    }
+}
+
+impl From<CryptoHash> for NsHash {
+    fn from(h: CryptoHash) -> Self {
+        NsHash::Hash(h)
+    }
+}
+
+impl FromStr for NsHash {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Tags always contain a separator, which is not valid in encoded hashes
+        if s.contains(':') {
+            return Ok(NsHash::Tag(s.to_string()));
+        }
+
+        let err = || {
+            format!(
+                "unrecognised hash '{}', expected an encoded hash or key:value tag",
+                s
+            )
+        };
+
+        // Encoded hashes are fixed length, mismatched lengths are rejected prior to decoding
+        if s.len() != CryptoHash::default().to_string().len() {
+            return Err(err());
+        }
+
+        CryptoHash::from_str(s).map(NsHash::Hash).map_err(|_| err())
+    }
+}
+
+impl fmt::Display for NsHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NsHash::Hash(h) => write!(f, "{}", h),
+            NsHash::Tag(t) => write!(f, "{}", t),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, StructOpt)]
+pub struct NsCreateOptions {
+    #[structopt(long)]
+    /// Name service prefix, applied to names prior to hashing
+    pub prefix: Option<String>,
+
+    #[structopt(long)]
+    /// Register the name service in the network for use by other peers
+    pub public: bool,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, StructOpt)]
+pub struct NsListOptions {
+    #[structopt(flatten)]
+    /// NameServer filter / selection
+    pub ns: ServiceIdentifier,
+
+    #[structopt(flatten)]
+    #[serde(default)]
+    pub page_bounds: PageBounds,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, StructOpt)]
+pub struct NsReverseOptions {
+    #[structopt(flatten)]
+    /// NameServer filter / selection
+    pub ns: ServiceIdentifier,
+
+    /// ID of the target service
+    pub target: Id,
+}
+
+/// Name service registration, returned by name service management commands
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct NsRegisterInfo {
     pub ns: Id,
     pub prefix: Option<String>,
 
+    #[serde(default)]
+    /// Registered service, `None` for name service creation
+    pub target: Option<Id>,
+
     pub name: Option<String>,
     pub hashes: Vec<CryptoHash>,
+
+    #[serde(default)]
+    /// Registration expiry
+    pub expiry: Option<SystemTime>,
+}
+
+/// Results of a name service search
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct NsSearchInfo {
+    pub ns: Id,
+    pub matches: Vec<NsMatch>,
+
+    #[serde(default)]
+    /// Cursor for fetching the following page of matches, where available
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Service matched by a name service search
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct NsMatch {
+    /// ID of the matched (target) service
+    pub target: Id,
+
+    /// Matched name, where the search was by name
+    pub name: Option<String>,
+    /// Matched hash
+    pub hash: CryptoHash,
+
+    /// Signature of the name service registration page
+    pub page: Signature,
+    /// Expiry of the registration
+    pub expiry: Option<SystemTime>,
+
+    /// Information for the target service, where known by the daemon
+    pub info: Option<ServiceInfo>,
+    /// Result of locating the target service, where resolved
+    pub located: Option<LocateInfo>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lookup derivation used in place of the name service (daemon) derivation
+    fn lookup(v: &str) -> Result<CryptoHash, ()> {
+        dsf_core::crypto::hash(v.as_bytes())
+    }
+
+    #[test]
+    fn resolve_tag_hashes() {
+        let h = NsHash::from_str("type:sensor").unwrap();
+        assert_eq!(h, NsHash::Tag("type:sensor".to_string()));
+        assert_eq!(h.resolve(lookup), lookup("type:sensor"));
+
+        let raw = lookup("kitchen").unwrap();
+        let h = NsHash::from_str(&raw.to_string()).unwrap();
+        assert_eq!(h, NsHash::Hash(raw.clone()));
+        assert_eq!(h.resolve(lookup), Ok(raw));
+    }
+
+    fn search(args: &[&str]) -> NsSearchOptions {
+        let args = ["search", "--index", "0"].iter().chain(args.iter());
+        NsSearchOptions::from_iter_safe(args).unwrap()
+    }
+
+    #[test]
+    fn glob_patterns() {
+        let tests = [
+            ("*", "", true),
+            ("*", "kitchen", true),
+            ("", "", true),
+            ("", "kitchen", false),
+            ("kitchen", "", false),
+            ("*chen", "kitchen", true),
+            ("*chen", "kitchens", false),
+            ("kit*", "kitchen", true),
+            ("kit*", "bathroom", false),
+            ("k*n", "kitchen", true),
+            ("k*n", "kitchens", false),
+            ("k*h*n", "kitchen", true),
+            ("*itc*", "kitchen", true),
+            ("kitche?", "kitchen", true),
+            ("?itchen", "kitchen", true),
+            ("kitchen?", "kitchen", false),
+            ("k??chen", "kitchen", true),
+            ("*?", "", false),
+        ];
+
+        for (pattern, value, matches) in &tests {
+            assert_eq!(glob_match(pattern, value), *matches, "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn match_names() {
+        let s = search(&[]);
+        assert!(s.matches_name(Some("kitchen")));
+        assert!(s.matches_name(None));
+
+        let s = search(&["--name-prefix", "kit"]);
+        assert!(s.matches_name(Some("kitchen")));
+        assert!(!s.matches_name(Some("bathroom")));
+        assert!(!s.matches_name(None));
+
+        let s = search(&["--glob", "*room"]);
+        assert!(s.matches_name(Some("bathroom")));
+        assert!(!s.matches_name(Some("kitchen")));
+        assert!(!s.matches_name(None));
+    }
+
+    #[test]
+    fn match_tag_hashes() {
+        let registered = |tags: &[&str]| -> Vec<CryptoHash> {
+            tags.iter().map(|t| lookup(t).unwrap()).collect()
+        };
+        let sensor = registered(&["room:kitchen", "type:temperature", "floor:1"]);
+        let light = registered(&["room:kitchen", "type:light"]);
+        let other = registered(&["room:bathroom"]);
+
+        // Services tagged room:kitchen and type:temperature
+        let s = search(&["--hash", "room:kitchen", "--hash", "type:temperature"]);
+        assert_eq!(s.hash_match, HashMatch::All);
+
+        let lookups = s.lookups(lookup).unwrap();
+        assert_eq!(lookups, registered(&["room:kitchen", "type:temperature"]));
+
+        assert!(s.matches_hashes(&lookups, &sensor));
+        assert!(!s.matches_hashes(&lookups, &light));
+        assert!(!s.matches_hashes(&lookups, &other));
+
+        // Services tagged room:kitchen or type:temperature
+        let s = search(&[
+            "--hash",
+            "room:kitchen",
+            "--hash",
+            "type:temperature",
+            "--match",
+            "any",
+        ]);
+        assert_eq!(s.hash_match, HashMatch::Any);
+
+        assert!(s.matches_hashes(&lookups, &sensor));
+        assert!(s.matches_hashes(&lookups, &light));
+        assert!(!s.matches_hashes(&lookups, &other));
+
+        // Searches without hashes match all services
+        assert!(s.matches_hashes(&[], &other));
+    }
 }
@@ -0,0 +1,941 @@
+//! Output formats for rendering daemon responses.
+//!
+//! Payloads implement [`Tabular`], providing column headers and row values from which
+//! text, table and CSV renderings are derived, while JSON and YAML renderings serialise
+//! payloads directly. Any `Tabular + Serialize` type, as well as [`ResponseKind`],
+//! implements [`Render`].
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use structopt::StructOpt;
+use strum::VariantNames;
+
+use dsf_core::types::*;
+use dsf_core::wire::Container;
+
+use crate::table::{terminal_width, Table};
+use crate::{AddressInfo, NsSearchInfo};
+use crate::ServiceKeys;
+use crate::{Body, ConnectInfo, DataInfo, HelloInfo, LocateInfo, NsRegisterInfo, Paged, PeerInfo};
+use crate::{PublishInfo, QueryInfo, RegisterInfo, ReplicaInfo, ResponseKind, ServiceInfo};
+use crate::{StatusInfo, SubscriptionInfo, SubscriptionKind, SyncInfo, VersionMismatch};
+
+/// Output format for rendering responses
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// Aligned table with a header row
+    Table,
+    /// Pretty-printed JSON
+    Json,
+    /// Comma separated values with a header row
+    Csv,
+    /// YAML
+    Yaml,
+}
+
+/// Output options, for inclusion in CLI arguments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct OutputOptions {
+    #[structopt(short = "o", long = "output", default_value = "text", possible_values = OutputFormat::VARIANTS)]
+    /// Output format for responses
+    pub output: OutputFormat,
+
+    #[structopt(long = "columns", use_delimiter = true)]
+    #[serde(default)]
+    /// Columns to include in table and CSV output (comma separated)
+    pub columns: Vec<String>,
+
+    #[structopt(long = "width")]
+    /// Maximum table width, defaults to the terminal width
+    pub width: Option<usize>,
+}
+
+impl OutputOptions {
+    pub fn new(output: OutputFormat) -> Self {
+        Self {
+            output,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            output: OutputFormat::Text,
+            columns: vec![],
+            width: None,
+        }
+    }
+}
+
+/// Errors returned when rendering responses
+#[derive(Debug)]
+pub enum RenderError {
+    /// Error serialising JSON output
+    Json(serde_json::Error),
+    /// Error serialising YAML output
+    Yaml(serde_yaml::Error),
+    /// Requested column does not exist
+    UnknownColumn(String),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::Json(e) => write!(f, "json error: {}", e),
+            RenderError::Yaml(e) => write!(f, "yaml error: {}", e),
+            RenderError::UnknownColumn(c) => write!(f, "unknown column: {}", c),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<serde_json::Error> for RenderError {
+    fn from(e: serde_json::Error) -> Self {
+        RenderError::Json(e)
+    }
+}
+
+impl From<serde_yaml::Error> for RenderError {
+    fn from(e: serde_yaml::Error) -> Self {
+        RenderError::Yaml(e)
+    }
+}
+
+/// Tabular trait provides column based representations of response payloads
+pub trait Tabular {
+    /// Column headers for rendered rows
+    fn headers() -> Vec<&'static str>;
+
+    /// Values for each rendered row, in the same order as the column headers
+    fn rows(&self) -> Vec<Vec<String>>;
+
+    /// Human readable text, defaults to `header: value` pairs for each row
+    fn text(&self) -> String {
+        let headers = Self::headers();
+
+        self.rows()
+            .iter()
+            .map(|r| {
+                headers
+                    .iter()
+                    .zip(r.iter())
+                    .map(|(h, v)| format!("{}: {}", h, v))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Render trait supports rendering responses in any [`OutputFormat`]
+pub trait Render {
+    /// Render using the provided output options
+    fn render_with(&self, options: &OutputOptions) -> Result<String, RenderError>;
+
+    /// Render in the provided format with default options
+    fn render(&self, format: OutputFormat) -> Result<String, RenderError> {
+        self.render_with(&OutputOptions::new(format))
+    }
+}
+
+impl<T: Tabular + Serialize> Render for T {
+    fn render_with(&self, options: &OutputOptions) -> Result<String, RenderError> {
+        let s = match options.output {
+            OutputFormat::Text => self.text(),
+            OutputFormat::Table => {
+                let width = options.width.or_else(terminal_width);
+
+                Table::from_tabular(self)
+                    .select(&options.columns)?
+                    .with_width(width)
+                    .to_string()
+            }
+            OutputFormat::Csv => Table::from_tabular(self).select(&options.columns)?.csv(),
+            OutputFormat::Json => serde_json::to_string_pretty(self)?,
+            OutputFormat::Yaml => serde_yaml::to_string(self)?,
+        };
+
+        Ok(s)
+    }
+}
+
+impl Render for ResponseKind {
+    fn render_with(&self, options: &OutputOptions) -> Result<String, RenderError> {
+        match self {
+            ResponseKind::Hello(i) => i.render_with(options),
+            ResponseKind::VersionMismatch(i) => i.render_with(options),
+            ResponseKind::Status(i) => i.render_with(options),
+            ResponseKind::Connected(i) => i.render_with(options),
+            ResponseKind::Peer(i) => i.render_with(options),
+            ResponseKind::Peers(i) => i.render_with(options),
+            ResponseKind::Service(i) => i.render_with(options),
+            ResponseKind::Services(i) => i.render_with(options),
+            ResponseKind::Keys(i) => i.render_with(options),
+            ResponseKind::Registered(i) => i.render_with(options),
+            ResponseKind::Located(i) => i.render_with(options),
+            ResponseKind::Subscribed(i) => i.render_with(options),
+            ResponseKind::Subscriber(i) => i.render_with(options),
+            ResponseKind::Subscribers(i) => i.render_with(options),
+            ResponseKind::Replicas(i) => i.render_with(options),
+            ResponseKind::Published(i) => i.render_with(options),
+            ResponseKind::Datastore(i) => i.render_with(options),
+            ResponseKind::Addresses(i) => i.render_with(options),
+            ResponseKind::Ns(i) => i.render_with(options),
+            ResponseKind::NsSearch(i) => i.render_with(options),
+            ResponseKind::NsRegistrations(i) => i.render_with(options),
+            ResponseKind::Data(i) => i.render_with(options),
+            ResponseKind::Query(i) => i.render_with(options),
+            ResponseKind::Synced(i) => i.render_with(options),
+            ResponseKind::Pages(i) => i.render_with(options),
+            ResponseKind::Page(i) => i.render_with(options),
+            ResponseKind::StreamOpen(i) => i.render_with(options),
+            ResponseKind::StreamItem(i) => i.render_with(options),
+            ResponseKind::Error(e) => {
+                Outcome::new(self.name(), Some(format!("{:?}", e))).render_with(options)
+            }
+            ResponseKind::None
+            | ResponseKind::StreamEnd
+            | ResponseKind::StreamCancelled
+            | ResponseKind::Unrecognised
+            | ResponseKind::Unauthorized => Outcome::new(self.name(), None).render_with(options),
+        }
+    }
+}
+
+/// Outcome of requests without an associated payload
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Outcome {
+    result: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl Outcome {
+    fn new(result: &'static str, detail: Option<String>) -> Self {
+        Self { result, detail }
+    }
+}
+
+impl Tabular for Outcome {
+    fn headers() -> Vec<&'static str> {
+        vec!["result", "detail"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![self.result.to_string(), opt(&self.detail)]]
+    }
+
+    fn text(&self) -> String {
+        match &self.detail {
+            Some(d) => format!("{}: {}", self.result, d),
+            None => self.result.to_string(),
+        }
+    }
+}
+
+/// Format optional values, `None` is rendered as an empty cell
+fn opt<T: fmt::Display>(v: &Option<T>) -> String {
+    match v {
+        Some(v) => v.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Format timestamps as RFC3339 (UTC) for stable machine readable output
+fn time(t: &Option<SystemTime>) -> String {
+    match t {
+        Some(t) => chrono::DateTime::<chrono::Utc>::from(*t).to_rfc3339(),
+        None => String::new(),
+    }
+}
+
+/// Format data bodies, cleartext is rendered as UTF-8 where valid or hex otherwise
+fn body(b: &Body) -> String {
+    match b {
+        Body::Cleartext(v) => match std::str::from_utf8(v) {
+            Ok(s) => s.to_string(),
+            Err(_) => v.iter().map(|b| format!("{:02x}", b)).collect(),
+        },
+        Body::Encrypted(_) => "encrypted".to_string(),
+        Body::None => String::new(),
+    }
+}
+
+fn join<T: fmt::Display>(v: &[T]) -> String {
+    v.iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl<T: Tabular> Tabular for Vec<T> {
+    fn headers() -> Vec<&'static str> {
+        T::headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.iter().flat_map(|v| v.rows()).collect()
+    }
+
+    fn text(&self) -> String {
+        self.iter().map(|v| v.text()).collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl<T: Tabular> Tabular for Paged<T> {
+    fn headers() -> Vec<&'static str> {
+        T::headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.items.rows()
+    }
+
+    fn text(&self) -> String {
+        self.items.text()
+    }
+}
+
+impl Tabular for HelloInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["protocol", "version", "capabilities"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.protocol.to_string(),
+            self.version.clone(),
+            join(&self.capabilities),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for VersionMismatch {
+    fn headers() -> Vec<&'static str> {
+        vec!["supported", "requested", "version"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.supported.to_string(),
+            self.requested.to_string(),
+            self.version.clone(),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Tabular for StatusInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["id", "peers", "services"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.id.to_string(),
+            self.peers.to_string(),
+            self.services.to_string(),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for ConnectInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["id", "peers"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![self.id.to_string(), self.peers.to_string()]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for PeerInfo {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "id", "index", "address", "state", "seen", "sent", "received", "blocked",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.id.to_string(),
+            self.index.to_string(),
+            SocketAddr::from(*self.address()).to_string(),
+            self.state.to_string(),
+            time(&self.seen),
+            self.sent.to_string(),
+            self.received.to_string(),
+            self.blocked.to_string(),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for (Id, PeerInfo) {
+    fn headers() -> Vec<&'static str> {
+        PeerInfo::headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.1.rows()
+    }
+
+    fn text(&self) -> String {
+        self.1.text()
+    }
+}
+
+impl Tabular for ServiceInfo {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "id",
+            "index",
+            "state",
+            "public_key",
+            "last_updated",
+            "subscribers",
+            "replicas",
+            "origin",
+            "subscribed",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.id.to_string(),
+            self.index.to_string(),
+            self.state.to_string(),
+            self.public_key.to_string(),
+            time(&self.last_updated),
+            self.subscribers.to_string(),
+            self.replicas.to_string(),
+            self.origin.to_string(),
+            self.subscribed.to_string(),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for ServiceKeys {
+    fn headers() -> Vec<&'static str> {
+        vec!["id", "public_key", "private_key", "secret_key"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.id.to_string(),
+            self.public_key.to_string(),
+            opt(&self.private_key),
+            opt(&self.secret_key),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for RegisterInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["page_version", "replica_version", "peers"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.page_version.to_string(),
+            opt(&self.replica_version),
+            self.peers.to_string(),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for LocateInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["origin", "updated", "page_version"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.origin.to_string(),
+            self.updated.to_string(),
+            self.page_version.to_string(),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for SubscriptionInfo {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "service_id",
+            "kind",
+            "updated",
+            "expiry",
+            "priority",
+            "delivery",
+            "rate_limit",
+            "batch_window_ms",
+            "max_backlog",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        let kind = match &self.kind {
+            SubscriptionKind::Peer(id) => format!("peer:{}", id),
+            SubscriptionKind::Socket(s) => format!("socket:{}", s),
+            SubscriptionKind::None => String::new(),
+        };
+
+        vec![vec![
+            self.service_id.to_string(),
+            kind,
+            time(&self.updated),
+            time(&self.expiry),
+            self.qos.priority.to_string(),
+            self.qos.delivery.to_string(),
+            opt(&self.qos.rate_limit),
+            opt(&self.qos.batch_window.map(|d| d.as_millis())),
+            opt(&self.qos.max_backlog),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for ReplicaInfo {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "service_id",
+            "page_id",
+            "peer_id",
+            "version",
+            "issued",
+            "updated",
+            "expiry",
+            "active",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.service_id.to_string(),
+            self.page_id.to_string(),
+            self.peer_id.to_string(),
+            self.version.to_string(),
+            time(&Some(self.issued)),
+            time(&Some(self.updated)),
+            time(&self.expiry),
+            self.active.to_string(),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for PublishInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["index"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![self.index.to_string()]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for (Id, Vec<Vec<u8>>) {
+    fn headers() -> Vec<&'static str> {
+        vec!["id", "pages", "bytes"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        let bytes: usize = self.1.iter().map(|p| p.len()).sum();
+
+        vec![vec![
+            self.0.to_string(),
+            self.1.len().to_string(),
+            bytes.to_string(),
+        ]]
+    }
+}
+
+impl Tabular for AddressInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["address", "kind", "preferred", "observed"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.address.to_string(),
+            self.kind.to_string(),
+            self.preferred.to_string(),
+            time(&self.observed),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for NsRegisterInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["ns", "prefix", "target", "name", "hashes", "expiry"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.ns.to_string(),
+            opt(&self.prefix),
+            opt(&self.target),
+            opt(&self.name),
+            join(&self.hashes),
+            time(&self.expiry),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for NsSearchInfo {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "target", "name", "hash", "page", "expiry", "state", "origin",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.matches
+            .iter()
+            .map(|m| {
+                vec![
+                    m.target.to_string(),
+                    opt(&m.name),
+                    m.hash.to_string(),
+                    m.page.to_string(),
+                    time(&m.expiry),
+                    opt(&m.info.as_ref().map(|i| i.state)),
+                    opt(&m.located.as_ref().map(|l| l.origin)),
+                ]
+            })
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for DataInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["service", "index", "previous", "signature", "body"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.service.to_string(),
+            self.index.to_string(),
+            opt(&self.previous),
+            self.signature.to_string(),
+            body(&self.body),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for QueryInfo {
+    fn headers() -> Vec<&'static str> {
+        DataInfo::headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.data.rows()
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for SyncInfo {
+    fn headers() -> Vec<&'static str> {
+        vec!["service", "fetched", "latest", "gaps", "peers"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.service.to_string(),
+            self.fetched.to_string(),
+            opt(&self.latest),
+            join(&self.gaps.iter().map(|g| g.index).collect::<Vec<_>>()),
+            join(&self.peers),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        format!("{:+}", self)
+    }
+}
+
+impl Tabular for Container {
+    fn headers() -> Vec<&'static str> {
+        vec!["id", "index", "signature", "encrypted"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.id().to_string(),
+            self.header().index().to_string(),
+            self.signature().to_string(),
+            self.encrypted().to_string(),
+        ]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dsf_core::error::Error;
+
+    use super::*;
+    use crate::{Cursor, PeerAddress, PeerState, ServiceState};
+
+    fn peers() -> ResponseKind {
+        let id = Id::from([1u8; 32]);
+        let address =
+            PeerAddress::Explicit("127.0.0.1:10100".parse::<SocketAddr>().unwrap().into());
+
+        let mut peer = PeerInfo::new(id.clone(), address, PeerState::Unknown, 0, None);
+        peer.sent = 3;
+        peer.received = 4;
+
+        let page = Paged::new(vec![(id, peer)], Some(Cursor::new(0))).with_total(2);
+        ResponseKind::Peers(page)
+    }
+
+    fn services() -> ResponseKind {
+        let service = ServiceInfo {
+            id: Id::from([2u8; 32]),
+            index: 1,
+            state: ServiceState::Registered,
+            public_key: PublicKey::from([3u8; 32]),
+            last_updated: None,
+            primary_page: None,
+            replica_page: None,
+            subscribers: 2,
+            replicas: 1,
+            origin: true,
+            subscribed: false,
+        };
+
+        ResponseKind::Services(Paged::from(vec![service]))
+    }
+
+    const ID1: &str = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=";
+    const ID2: &str = "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=";
+    const KEY3: &str = "AwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwM=";
+
+    fn json(r: &ResponseKind) -> serde_json::Value {
+        serde_json::from_str(&r.render(OutputFormat::Json).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn render_peers() {
+        let r = peers();
+
+        let peer = serde_json::json!({
+            "id": ID1,
+            "index": 0,
+            "address": { "Explicit": { "ip": { "V4": [127, 0, 0, 1] }, "port": 10100 } },
+            "state": "Unknown",
+            "seen": null,
+            "sent": 3,
+            "received": 4,
+            "blocked": false,
+        });
+        assert_eq!(
+            json(&r),
+            serde_json::json!({
+                "items": [[ID1, peer]],
+                "next_cursor": { "index": 0, "signature": null },
+                "total": 2,
+            })
+        );
+
+        assert_eq!(
+            r.render(OutputFormat::Csv).unwrap(),
+            format!(
+                "id,index,address,state,seen,sent,received,blocked\n\
+                 {},0,127.0.0.1:10100,Unknown,,3,4,false",
+                ID1
+            )
+        );
+
+        assert_eq!(
+            r.render(OutputFormat::Yaml).unwrap(),
+            format!(
+                "---\n\
+                 items:\n  \
+                 - - {0}\n    \
+                 - id: {0}\n      \
+                 index: 0\n      \
+                 address:\n        \
+                 Explicit:\n          \
+                 ip:\n            \
+                 V4:\n              \
+                 - 127\n              \
+                 - 0\n              \
+                 - 0\n              \
+                 - 1\n          \
+                 port: 10100\n      \
+                 state: Unknown\n      \
+                 seen: ~\n      \
+                 sent: 3\n      \
+                 received: 4\n      \
+                 blocked: false\n\
+                 next_cursor:\n  \
+                 index: 0\n  \
+                 signature: ~\n\
+                 total: 2\n",
+                ID1
+            )
+        );
+    }
+
+    #[test]
+    fn render_services() {
+        let r = services();
+
+        let service = serde_json::json!({
+            "id": ID2,
+            "index": 1,
+            "state": "Registered",
+            "public_key": KEY3,
+            "last_updated": null,
+            "primary_page": null,
+            "replica_page": null,
+            "subscribers": 2,
+            "replicas": 1,
+            "origin": true,
+            "subscribed": false,
+        });
+        assert_eq!(
+            json(&r),
+            serde_json::json!({ "items": [service], "next_cursor": null, "total": null })
+        );
+
+        assert_eq!(
+            r.render(OutputFormat::Csv).unwrap(),
+            format!(
+                "id,index,state,public_key,last_updated,subscribers,replicas,origin,subscribed\n\
+                 {},1,Registered,{},,2,1,true,false",
+                ID2, KEY3
+            )
+        );
+
+        assert_eq!(
+            r.render(OutputFormat::Yaml).unwrap(),
+            format!(
+                "---\n\
+                 items:\n  \
+                 - id: {}\n    \
+                 index: 1\n    \
+                 state: Registered\n    \
+                 public_key: {}\n    \
+                 last_updated: ~\n    \
+                 primary_page: ~\n    \
+                 replica_page: ~\n    \
+                 subscribers: 2\n    \
+                 replicas: 1\n    \
+                 origin: true\n    \
+                 subscribed: false\n\
+                 next_cursor: ~\n\
+                 total: ~\n",
+                ID2, KEY3
+            )
+        );
+    }
+
+    #[test]
+    fn render_outcomes() {
+        let r = ResponseKind::Error(Error::Time);
+        assert_eq!(
+            json(&r),
+            serde_json::json!({ "result": "Error", "detail": "Time" })
+        );
+        assert_eq!(
+            r.render(OutputFormat::Csv).unwrap(),
+            "result,detail\nError,Time"
+        );
+        assert_eq!(
+            r.render(OutputFormat::Yaml).unwrap(),
+            "---\nresult: Error\ndetail: Time\n"
+        );
+
+        let r = ResponseKind::None;
+        assert_eq!(json(&r), serde_json::json!({ "result": "None" }));
+        assert_eq!(r.render(OutputFormat::Csv).unwrap(), "result,detail\nNone,");
+        assert_eq!(r.render(OutputFormat::Yaml).unwrap(), "---\nresult: None\n");
+    }
+}
@@ -0,0 +1,367 @@
+//! Cursor based pagination.
+//!
+//! List responses are returned as [`Paged`] objects, containing a `next_cursor`
+//! identifying the last returned object where further results are available.
+//! Passing this cursor in the [`PageBounds`] of a subsequent request returns results
+//! following the cursor, avoiding skipped or duplicated results where objects are
+//! added between requests. Where the cursor object has since been removed, results
+//! ordered by index resume from the following object.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use futures::prelude::*;
+use futures::stream;
+
+use dsf_core::types::*;
+
+use crate::{DataInfo, PageBounds, Request, RequestKind, Response, Rpc, UnexpectedResponse};
+
+/// Opaque pagination cursor, identifying the last object returned in a page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// Index of the last returned object
+    pub index: usize,
+    /// Signature of the last returned object, where applicable
+    pub signature: Option<Signature>,
+}
+
+impl Cursor {
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            signature: None,
+        }
+    }
+
+    pub fn with_signature(index: usize, signature: Signature) -> Self {
+        Self {
+            index,
+            signature: Some(signature),
+        }
+    }
+
+    /// Check whether a cursor identifies the same object, comparing signatures
+    /// where both cursors include them
+    pub fn matches(&self, other: &Cursor) -> bool {
+        if self.index != other.index {
+            return false;
+        }
+
+        match (&self.signature, &other.signature) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+    }
+}
+
+impl From<&DataInfo> for Cursor {
+    fn from(d: &DataInfo) -> Self {
+        Self::with_signature(d.index as usize, d.signature.clone())
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.signature {
+            Some(s) => write!(f, "{}.{}", self.index, s),
+            None => write!(f, "{}", self.index),
+        }
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.splitn(2, '.');
+
+        let index = split
+            .next()
+            .unwrap_or("")
+            .parse()
+            .map_err(|_| format!("invalid cursor index in '{}'", s))?;
+
+        let signature = match split.next() {
+            Some(sig) => Some(
+                Signature::from_str(sig)
+                    .map_err(|_| format!("invalid cursor signature in '{}'", s))?,
+            ),
+            None => None,
+        };
+
+        Ok(Self { index, signature })
+    }
+}
+
+/// Index ordering of a result set, used to resume paging where the cursor object
+/// is no longer present
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndexOrder {
+    /// Results are sorted by ascending index
+    Ascending,
+    /// Results are sorted by descending index
+    Descending,
+    /// Results are sorted by another key
+    Unordered,
+}
+
+/// Errors selecting a page of results
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageError {
+    /// The cursor object is no longer present, and results are not ordered by index
+    /// so the following page cannot be located
+    CursorNotFound(Cursor),
+}
+
+impl fmt::Display for PageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PageError::CursorNotFound(c) => write!(f, "cursor {} not found", c),
+        }
+    }
+}
+
+impl std::error::Error for PageError {}
+
+/// Page of results returned in response to a list request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Paged<T> {
+    /// Results for this page
+    pub items: Vec<T>,
+    /// Cursor for fetching the following page, `None` where no further results are available
+    pub next_cursor: Option<Cursor>,
+    /// Total number of results matching the request, where known
+    #[serde(default)]
+    pub total: Option<usize>,
+}
+
+impl<T> Paged<T> {
+    pub fn new(items: Vec<T>, next_cursor: Option<Cursor>) -> Self {
+        Self {
+            items,
+            next_cursor,
+            total: None,
+        }
+    }
+
+    /// Set the total number of matching results
+    pub fn with_total(mut self, total: usize) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Select a page of results from a complete (filtered and sorted) result set.
+    ///
+    /// Results following the bounds cursor (matched against the `cursor` for each object)
+    /// or offset are returned, limited by the bounds count. Where the cursor object is
+    /// no longer present, results resume from the first object past the cursor index
+    /// in the provided `order`, or a [`PageError`] is returned for unordered results.
+    pub fn select<F>(
+        mut all: Vec<T>,
+        bounds: &PageBounds,
+        order: IndexOrder,
+        cursor: F,
+    ) -> Result<Self, PageError>
+    where
+        F: Fn(&T) -> Cursor,
+    {
+        let total = all.len();
+
+        let start = match (&bounds.cursor, bounds.offset) {
+            (Some(c), _) => match all.iter().position(|v| cursor(v).matches(c)) {
+                Some(p) => p + 1,
+                None => {
+                    let past = match order {
+                        IndexOrder::Ascending => all.iter().position(|v| cursor(v).index > c.index),
+                        IndexOrder::Descending => {
+                            all.iter().position(|v| cursor(v).index < c.index)
+                        }
+                        IndexOrder::Unordered => return Err(PageError::CursorNotFound(c.clone())),
+                    };
+                    past.unwrap_or(total)
+                }
+            },
+            (None, Some(offset)) => offset.min(total),
+            (None, None) => 0,
+        };
+
+        let end = match bounds.count {
+            Some(count) => start.saturating_add(count).min(total),
+            None => total,
+        };
+
+        let items: Vec<T> = all.drain(start..end).collect();
+
+        let next_cursor = match items.last() {
+            Some(last) if end < total => Some(cursor(last)),
+            _ => None,
+        };
+
+        Ok(Self::new(items, next_cursor).with_total(total))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+}
+
+impl<T> From<Vec<T>> for Paged<T> {
+    /// Create a single (final) page of results
+    fn from(items: Vec<T>) -> Self {
+        Self::new(items, None)
+    }
+}
+
+impl<T> IntoIterator for Paged<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// Lazily fetch all results for a paginated request.
+///
+/// `request` builds the request for each page from the provided bounds, with pages fetched
+/// as the returned stream is consumed. The stream completes following a page without a
+/// `next_cursor`, or after yielding the first error.
+pub fn paginate<'a, R, T, F>(
+    rpc: &'a mut R,
+    bounds: PageBounds,
+    request: F,
+) -> impl Stream<Item = Result<T, R::Error>> + 'a
+where
+    R: Rpc + 'a,
+    T: 'a,
+    Paged<T>: TryFrom<Response, Error = UnexpectedResponse>,
+    F: FnMut(PageBounds) -> RequestKind + 'a,
+{
+    let pages = stream::unfold(Some((rpc, request, bounds)), |state| async move {
+        let (rpc, mut request, bounds) = state?;
+
+        let req = Request::new(request(bounds.clone()));
+        let page = match rpc.exec(req).await {
+            Ok(resp) => Paged::<T>::try_from(resp).map_err(R::Error::from),
+            Err(e) => Err(e),
+        };
+
+        match page {
+            Ok(p) => {
+                let next = p.next_cursor.map(|c| (rpc, request, bounds.after(c)));
+                Some((Ok(p.items), next))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+
+    pages
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(count: usize, cursor: Option<Cursor>) -> PageBounds {
+        PageBounds {
+            count: Some(count),
+            offset: None,
+            cursor,
+        }
+    }
+
+    fn select(
+        all: &[usize],
+        bounds: &PageBounds,
+        order: IndexOrder,
+    ) -> Result<Paged<usize>, PageError> {
+        Paged::select(all.to_vec(), bounds, order, |v| Cursor::new(*v))
+    }
+
+    #[test]
+    fn select_pages() {
+        let all = [1, 2, 3, 4, 5];
+
+        let p = select(&all, &bounds(2, None), IndexOrder::Ascending).unwrap();
+        assert_eq!(p.items, vec![1, 2]);
+        assert_eq!(p.next_cursor, Some(Cursor::new(2)));
+        assert_eq!(p.total, Some(5));
+
+        let p = select(&all, &bounds(2, p.next_cursor), IndexOrder::Ascending).unwrap();
+        assert_eq!(p.items, vec![3, 4]);
+
+        let p = select(&all, &bounds(2, p.next_cursor), IndexOrder::Ascending).unwrap();
+        assert_eq!(p.items, vec![5]);
+        assert_eq!(p.next_cursor, None);
+    }
+
+    #[test]
+    fn select_large_count() {
+        let all = [1, 2, 3];
+
+        let p = select(
+            &all,
+            &bounds(usize::MAX, Some(Cursor::new(1))),
+            IndexOrder::Ascending,
+        );
+        assert_eq!(p.unwrap().items, vec![2, 3]);
+    }
+
+    #[test]
+    fn select_missing_cursor() {
+        let b = bounds(2, Some(Cursor::new(3)));
+
+        // Results resume past the removed cursor object, in either direction
+        let p = select(&[1, 2, 4, 5, 6], &b, IndexOrder::Ascending).unwrap();
+        assert_eq!(p.items, vec![4, 5]);
+
+        let p = select(&[6, 5, 4, 2, 1], &b, IndexOrder::Descending).unwrap();
+        assert_eq!(p.items, vec![2, 1]);
+
+        let p = select(&[1, 2], &b, IndexOrder::Ascending).unwrap();
+        assert!(p.items.is_empty());
+        assert_eq!(p.next_cursor, None);
+
+        // Unordered results can not be resumed
+        let r = select(&[5, 1, 4, 2], &b, IndexOrder::Unordered);
+        assert_eq!(r, Err(PageError::CursorNotFound(Cursor::new(3))));
+    }
+
+    #[test]
+    fn select_checks_signatures() {
+        let (a, b) = (Signature::from([1u8; 64]), Signature::from([2u8; 64]));
+        let all = vec![(1, a.clone()), (2, b.clone()), (3, a.clone())];
+        let cursor = |(i, s): &(usize, Signature)| Cursor::with_signature(*i, s.clone());
+
+        let p = Paged::select(
+            all.clone(),
+            &bounds(1, Some(Cursor::with_signature(2, b))),
+            IndexOrder::Ascending,
+            cursor,
+        )
+        .unwrap();
+        assert_eq!(p.items, vec![(3, a.clone())]);
+
+        // Objects replaced at the cursor index are treated as missing
+        let replaced = Cursor::with_signature(2, a);
+        let r = Paged::select(
+            all,
+            &bounds(1, Some(replaced.clone())),
+            IndexOrder::Unordered,
+            cursor,
+        );
+        assert_eq!(r, Err(PageError::CursorNotFound(replaced)));
+    }
+}
@@ -2,11 +2,12 @@ use std::net::SocketAddr;
 use std::time::{Duration, SystemTime};
 
 use structopt::StructOpt;
+use strum::VariantNames;
 
 use dsf_core::types::*;
 
 use crate::helpers::{parse_duration, try_parse_sock_addr};
-use crate::ServiceIdentifier;
+use crate::{Cursor, IndexOrder, PageBounds, PageError, Paged, ServiceIdentifier};
 
 /// PeerState defines the state of a peer
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Display)]
@@ -148,7 +149,147 @@ pub struct ConnectOptions {
 
 // Peer list options
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
-pub struct PeerOptions {}
+pub struct PeerOptions {
+    #[structopt(flatten)]
+    #[serde(default)]
+    pub page_bounds: PageBounds,
+
+    #[structopt(long = "sort", default_value = "index", possible_values = PeerSort::VARIANTS)]
+    #[serde(default)]
+    /// Sort peers by the provided key
+    pub sort: PeerSort,
+
+    #[structopt(long = "reverse")]
+    #[serde(default)]
+    /// Reverse sort order
+    pub reverse: bool,
+
+    #[structopt(long = "state", possible_values = PeerStateKind::VARIANTS)]
+    /// Filter peers by state
+    pub state: Option<PeerStateKind>,
+
+    #[structopt(long = "blocked")]
+    /// Filter peers by blocked status
+    pub blocked: Option<bool>,
+}
+
+impl Default for PeerOptions {
+    fn default() -> Self {
+        Self {
+            page_bounds: PageBounds::default(),
+            sort: PeerSort::Index,
+            reverse: false,
+            state: None,
+            blocked: None,
+        }
+    }
+}
+
+impl PeerOptions {
+    /// Check whether a peer matches the list filters
+    pub fn matches(&self, peer: &PeerInfo) -> bool {
+        if let Some(s) = self.state {
+            if PeerStateKind::from(&peer.state) != s {
+                return false;
+            }
+        }
+
+        if let Some(b) = self.blocked {
+            if peer.blocked != b {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Sort peers using the list sort key and order
+    pub fn sort(&self, peers: &mut [(Id, PeerInfo)]) {
+        match self.sort {
+            PeerSort::Index => peers.sort_by_key(|(_, p)| p.index),
+            PeerSort::Seen => peers.sort_by_key(|(_, p)| p.seen),
+            PeerSort::Sent => peers.sort_by_key(|(_, p)| p.sent),
+            PeerSort::Received => peers.sort_by_key(|(_, p)| p.received),
+            PeerSort::State => peers.sort_by_key(|(_, p)| PeerStateKind::from(&p.state)),
+        }
+
+        if self.reverse {
+            peers.reverse();
+        }
+    }
+
+    /// Filter, sort and select a page of peers
+    pub fn apply(
+        &self,
+        mut peers: Vec<(Id, PeerInfo)>,
+    ) -> Result<Paged<(Id, PeerInfo)>, PageError> {
+        peers.retain(|(_, p)| self.matches(p));
+        self.sort(&mut peers);
+
+        let order = match (self.sort, self.reverse) {
+            (PeerSort::Index, false) => IndexOrder::Ascending,
+            (PeerSort::Index, true) => IndexOrder::Descending,
+            _ => IndexOrder::Unordered,
+        };
+
+        Paged::select(peers, &self.page_bounds, order, |(_, p)| {
+            Cursor::new(p.index)
+        })
+    }
+}
+
+/// Sort keys for peer listings
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum PeerSort {
+    #[default]
+    Index,
+    Seen,
+    Sent,
+    Received,
+    State,
+}
+
+/// Peer state without associated data, for filtering and sorting
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum PeerStateKind {
+    Unknown,
+    Known,
+}
+
+impl From<&PeerState> for PeerStateKind {
+    fn from(s: &PeerState) -> Self {
+        match s {
+            PeerState::Unknown => PeerStateKind::Unknown,
+            PeerState::Known(_) => PeerStateKind::Known,
+        }
+    }
+}
 
 /// ConnectOptions passed to connect function
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
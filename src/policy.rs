@@ -0,0 +1,299 @@
+//! Role based access control for RPC requests.
+//!
+//! Every request is classified as requiring [`Access::Read`], [`Access::Write`]
+//! or [`Access::Admin`] permissions, with a [`Policy`] describing the access granted
+//! to each (authenticated) principal.
+
+use std::collections::HashMap;
+
+use crate::{ConfigCommands, DataCommands, DebugCommands, NsCommands, PageCommands, PeerCommands};
+use crate::{DelegationCommands, ReplicaCommands};
+use crate::{Principal, Request, RequestKind, ServiceCommands, SubscriberCommands};
+
+/// Access level required to execute a request, levels include all lower levels
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum Access {
+    /// Read-only access to daemon state
+    Read,
+    /// Access to commands that modify services, peers or data
+    Write,
+    /// Access to daemon configuration, debug and key management commands
+    Admin,
+}
+
+impl RequestKind {
+    /// Fetch the access level required to execute a request
+    pub fn access(&self) -> Access {
+        match self {
+            RequestKind::Hello(_) | RequestKind::Status => Access::Read,
+            RequestKind::Peer(c) => c.access(),
+            RequestKind::Service(c) => c.access(),
+            RequestKind::Ns(c) => c.access(),
+            RequestKind::Page(c) => c.access(),
+            RequestKind::Data(c) => c.access(),
+            RequestKind::Subscriber(c) => c.access(),
+            RequestKind::Replica(c) => c.access(),
+            RequestKind::Delegate(c) => c.access(),
+            RequestKind::Config(c) => c.access(),
+            RequestKind::Debug(c) => c.access(),
+            RequestKind::Stream(_) | RequestKind::StreamCancel(_) | RequestKind::StreamAck(_) => {
+                Access::Read
+            }
+        }
+    }
+}
+
+impl PeerCommands {
+    pub fn access(&self) -> Access {
+        match self {
+            PeerCommands::List(_) | PeerCommands::Info(_) | PeerCommands::Search(_) => Access::Read,
+            PeerCommands::Connect(_) | PeerCommands::Remove(_) => Access::Write,
+            PeerCommands::Block(_) | PeerCommands::Unblock(_) => Access::Admin,
+        }
+    }
+}
+
+impl ServiceCommands {
+    pub fn access(&self) -> Access {
+        match self {
+            ServiceCommands::List(_) | ServiceCommands::Info(_) | ServiceCommands::Locate(_) => {
+                Access::Read
+            }
+            ServiceCommands::Create(_)
+            | ServiceCommands::Register(_)
+            | ServiceCommands::Subscribe(_)
+            | ServiceCommands::Unsubscribe(_) => Access::Write,
+            // Purging removes owned services from the network
+            ServiceCommands::Remove(o) if o.purge => Access::Admin,
+            ServiceCommands::Remove(_) => Access::Write,
+            ServiceCommands::SetKey(_) | ServiceCommands::ExportKeys(_) => Access::Admin,
+        }
+    }
+}
+
+impl NsCommands {
+    pub fn access(&self) -> Access {
+        match self {
+            NsCommands::Search(_) | NsCommands::List(_) | NsCommands::Reverse(_) => Access::Read,
+            NsCommands::Create(_)
+            | NsCommands::Register(_)
+            | NsCommands::Deregister(_)
+            | NsCommands::Renew(_) => Access::Write,
+        }
+    }
+}
+
+impl PageCommands {
+    pub fn access(&self) -> Access {
+        match self {
+            PageCommands::Fetch(_) => Access::Read,
+        }
+    }
+}
+
+impl DataCommands {
+    pub fn access(&self) -> Access {
+        match self {
+            DataCommands::List(_) | DataCommands::Query(_) => Access::Read,
+            DataCommands::Update(_) | DataCommands::Publish(_) | DataCommands::Push(_) => {
+                Access::Write
+            }
+        }
+    }
+}
+
+impl SubscriberCommands {
+    pub fn access(&self) -> Access {
+        match self {
+            SubscriberCommands::List(_) => Access::Read,
+            SubscriberCommands::Remove(_)
+            | SubscriberCommands::Extend(_)
+            | SubscriberCommands::SetQos(_) => Access::Write,
+        }
+    }
+}
+
+impl ReplicaCommands {
+    pub fn access(&self) -> Access {
+        match self {
+            ReplicaCommands::List(_) | ReplicaCommands::Hosted => Access::Read,
+            ReplicaCommands::Drop(_)
+            | ReplicaCommands::Renew(_)
+            | ReplicaCommands::SetExpiry(_) => Access::Write,
+        }
+    }
+}
+
+impl DelegationCommands {
+    pub fn access(&self) -> Access {
+        match self {
+            DelegationCommands::Hello => Access::Read,
+            DelegationCommands::Register(_) | DelegationCommands::Publish(_) => Access::Write,
+        }
+    }
+}
+
+impl ConfigCommands {
+    pub fn access(&self) -> Access {
+        match self {
+            ConfigCommands::ListAddresses => Access::Read,
+            ConfigCommands::AddAddress(_)
+            | ConfigCommands::RemoveAddress(_)
+            | ConfigCommands::SetPreferredAddress(_) => Access::Admin,
+        }
+    }
+}
+
+impl DebugCommands {
+    pub fn access(&self) -> Access {
+        Access::Admin
+    }
+}
+
+/// Access control policy, mapping principals to granted access levels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Policy {
+    /// Access granted to unauthenticated clients
+    pub anonymous: Option<Access>,
+    /// Access granted to authenticated principals without an explicit grant
+    pub default: Option<Access>,
+    /// Access granted to specific principals
+    pub grants: HashMap<Principal, Access>,
+}
+
+impl Default for Policy {
+    /// Default policy denies all requests
+    fn default() -> Self {
+        Self {
+            anonymous: None,
+            default: None,
+            grants: HashMap::new(),
+        }
+    }
+}
+
+impl Policy {
+    /// Create a policy granting admin access to all clients, for single user hosts
+    pub fn open() -> Self {
+        Self {
+            anonymous: Some(Access::Admin),
+            default: Some(Access::Admin),
+            grants: HashMap::new(),
+        }
+    }
+
+    /// Grant access to a principal
+    pub fn grant(mut self, principal: Principal, access: Access) -> Self {
+        self.grants.insert(principal, access);
+        self
+    }
+
+    /// Set access for unauthenticated clients
+    pub fn with_anonymous(mut self, access: Option<Access>) -> Self {
+        self.anonymous = access;
+        self
+    }
+
+    /// Set access for authenticated principals without an explicit grant
+    pub fn with_default(mut self, access: Option<Access>) -> Self {
+        self.default = access;
+        self
+    }
+
+    /// Fetch the access level granted to a principal (or unauthenticated client)
+    pub fn access(&self, principal: Option<&Principal>) -> Option<Access> {
+        match principal {
+            Some(p) => self.grants.get(p).cloned().or(self.default),
+            None => self.anonymous,
+        }
+    }
+
+    /// Check whether a principal (or unauthenticated client) may execute a request
+    pub fn allows(&self, principal: Option<&Principal>, req: &Request) -> bool {
+        match self.access(principal) {
+            Some(granted) => granted >= req.kind.access(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use structopt::StructOpt;
+
+    fn request(args: &[&str]) -> Request {
+        let args = std::iter::once("dsfc").chain(args.iter().cloned());
+        Request::new(RequestKind::from_iter_safe(args).unwrap())
+    }
+
+    #[test]
+    fn request_access() {
+        let tests: &[(&[&str], Access)] = &[
+            (&["status"], Access::Read),
+            (&["peer", "list"], Access::Read),
+            (&["peer", "block", "--index", "0"], Access::Admin),
+            (&["service", "list"], Access::Read),
+            (&["service", "remove", "--index", "0"], Access::Write),
+            (
+                &["service", "remove", "--index", "0", "--purge"],
+                Access::Admin,
+            ),
+            (&["service", "set-key", "--index", "0"], Access::Admin),
+            (&["service", "export-keys", "--index", "0"], Access::Admin),
+            (&["data", "list", "--index", "0"], Access::Read),
+            (&["data", "publish", "--index", "0"], Access::Write),
+            (&["config", "add-address", "127.0.0.1:10100"], Access::Admin),
+        ];
+
+        for (args, access) in tests {
+            assert_eq!(request(args).kind.access(), *access, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn read_only_policy() {
+        let reader = Principal("reader".to_string());
+        let policy = Policy::default().grant(reader.clone(), Access::Read);
+
+        let allowed: &[&[&str]] = &[
+            &["status"],
+            &["peer", "list"],
+            &["service", "list"],
+            &["data", "list", "--index", "0"],
+        ];
+        for args in allowed {
+            assert!(policy.allows(Some(&reader), &request(args)), "{:?}", args);
+        }
+
+        let denied: &[&[&str]] = &[
+            &["data", "publish", "--index", "0"],
+            &["service", "remove", "--index", "0", "--purge"],
+            &["peer", "block", "--index", "0"],
+            &["config", "add-address", "127.0.0.1:10100"],
+        ];
+        for args in denied {
+            assert!(!policy.allows(Some(&reader), &request(args)), "{:?}", args);
+        }
+
+        // Unknown principals and unauthenticated clients are denied by default
+        let other = Principal("other".to_string());
+        assert!(!policy.allows(Some(&other), &request(&["status"])));
+        assert!(!policy.allows(None, &request(&["status"])));
+    }
+}
@@ -0,0 +1,157 @@
+//! Protocol version negotiation.
+//!
+//! Clients issue a `RequestKind::Hello` containing the local [`HelloInfo`], the daemon
+//! responds with its own `ResponseKind::Hello` where the protocol versions are compatible,
+//! or a `ResponseKind::VersionMismatch` otherwise.
+
+use std::fmt;
+
+use structopt::StructOpt;
+
+use crate::RequestKind;
+
+/// RPC protocol version, incremented on incompatible changes to RPC messages
+pub const PROTOCOL_VERSION: u16 = 2;
+
+/// Request families that may be supported by a daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Capability {
+    Status,
+    Peer,
+    Service,
+    Ns,
+    Page,
+    Data,
+    Subscriber,
+    Replica,
+    Delegation,
+    Config,
+    Debug,
+    Stream,
+}
+
+impl Capability {
+    /// All known capabilities
+    pub const ALL: &'static [Capability] = &[
+        Capability::Status,
+        Capability::Peer,
+        Capability::Service,
+        Capability::Ns,
+        Capability::Page,
+        Capability::Data,
+        Capability::Subscriber,
+        Capability::Replica,
+        Capability::Delegation,
+        Capability::Config,
+        Capability::Debug,
+        Capability::Stream,
+    ];
+}
+
+impl RequestKind {
+    /// Fetch the capability required to service a request,
+    /// `None` for requests that must always be supported
+    pub fn capability(&self) -> Option<Capability> {
+        let c = match self {
+            RequestKind::Hello(_) => return None,
+            RequestKind::Status => Capability::Status,
+            RequestKind::Peer(_) => Capability::Peer,
+            RequestKind::Service(_) => Capability::Service,
+            RequestKind::Ns(_) => Capability::Ns,
+            RequestKind::Page(_) => Capability::Page,
+            RequestKind::Data(_) => Capability::Data,
+            RequestKind::Subscriber(_) => Capability::Subscriber,
+            RequestKind::Replica(_) => Capability::Replica,
+            RequestKind::Delegate(_) => Capability::Delegation,
+            RequestKind::Config(_) => Capability::Config,
+            RequestKind::Debug(_) => Capability::Debug,
+            RequestKind::Stream(_) | RequestKind::StreamCancel(_) | RequestKind::StreamAck(_) => {
+                Capability::Stream
+            }
+        };
+
+        Some(c)
+    }
+}
+
+/// Handshake information exchanged between clients and the daemon
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct HelloInfo {
+    #[structopt(skip = PROTOCOL_VERSION)]
+    /// RPC protocol version
+    pub protocol: u16,
+
+    #[structopt(skip = env!("CARGO_PKG_VERSION").to_string())]
+    /// Implementation (crate) version
+    pub version: String,
+
+    #[structopt(skip = Capability::ALL.to_vec())]
+    /// Supported request families
+    pub capabilities: Vec<Capability>,
+}
+
+impl Default for HelloInfo {
+    fn default() -> Self {
+        Self::new(Capability::ALL.to_vec())
+    }
+}
+
+impl HelloInfo {
+    /// Create handshake information for the local protocol version with the provided capabilities
+    pub fn new(capabilities: Vec<Capability>) -> Self {
+        Self {
+            protocol: PROTOCOL_VERSION,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            capabilities,
+        }
+    }
+
+    /// Check whether a capability is supported
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    /// Check compatibility with a remote handshake, returning the capabilities supported by both
+    pub fn negotiate(&self, remote: &HelloInfo) -> Result<Vec<Capability>, VersionMismatch> {
+        if self.protocol != remote.protocol {
+            return Err(VersionMismatch {
+                supported: self.protocol,
+                requested: remote.protocol,
+                version: self.version.clone(),
+            });
+        }
+
+        let shared = self
+            .capabilities
+            .iter()
+            .filter(|c| remote.supports(**c))
+            .cloned()
+            .collect();
+
+        Ok(shared)
+    }
+}
+
+/// Protocol version mismatch, returned by the daemon in response to an incompatible handshake
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionMismatch {
+    /// Protocol version supported by the daemon
+    pub supported: u16,
+    /// Protocol version requested by the client
+    pub requested: u16,
+    /// Daemon implementation version
+    pub version: String,
+}
+
+impl fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "protocol version mismatch: daemon {} supports protocol v{}, client requested v{}",
+            self.version, self.supported, self.requested
+        )
+    }
+}
+
+impl std::error::Error for VersionMismatch {}
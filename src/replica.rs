@@ -1,10 +1,14 @@
 use std::time::SystemTime;
 
+use structopt::StructOpt;
+
 use dsf_core::prelude::*;
 
+use crate::{timestamp_from_str, ServiceIdentifier};
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReplicaInfo {
-    //pub service_id: Id,
+    pub service_id: Id,
     pub page_id: Id,
     pub peer_id: Id,
 
@@ -15,3 +19,48 @@ pub struct ReplicaInfo {
     pub expiry: Option<SystemTime>,
     pub active: bool,
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub enum ReplicaCommands {
+    #[structopt(name = "list")]
+    /// List known replicas for a service
+    List(ReplicaOptions),
+
+    #[structopt(name = "hosted")]
+    /// List replicas hosted by this daemon
+    Hosted,
+
+    #[structopt(name = "drop")]
+    /// Drop a replica hosted by this daemon
+    Drop(ReplicaOptions),
+
+    #[structopt(name = "renew")]
+    /// Renew (re-issue) a replica hosted by this daemon
+    Renew(ReplicaOptions),
+
+    #[structopt(name = "set-expiry")]
+    /// Set the expiry for a replica hosted by this daemon
+    SetExpiry(ReplicaExpiryOptions),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct ReplicaOptions {
+    #[structopt(flatten)]
+    pub service: ServiceIdentifier,
+}
+
+impl From<ServiceIdentifier> for ReplicaOptions {
+    fn from(service: ServiceIdentifier) -> Self {
+        Self { service }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct ReplicaExpiryOptions {
+    #[structopt(flatten)]
+    pub service: ServiceIdentifier,
+
+    #[structopt(long = "expiry", parse(try_from_str = timestamp_from_str))]
+    /// Replica expiry time
+    pub expiry: SystemTime,
+}
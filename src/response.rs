@@ -0,0 +1,156 @@
+//! Typed extraction of response payloads.
+//!
+//! Each payload carrying [`ResponseKind`] variant has a matching `Response::into_*` method
+//! (and a `TryFrom<Response>` implementation where the payload type is unique to the variant),
+//! returning an [`UnexpectedResponse`] error where the daemon responds with a different kind.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use dsf_core::error::Error;
+use dsf_core::types::*;
+use dsf_core::wire::Container;
+
+use crate::{AddressInfo, VersionMismatch};
+use crate::{ConnectInfo, DataInfo, LocateInfo, NsRegisterInfo, PeerInfo, PublishInfo, QueryInfo};
+use crate::{HelloInfo, RegisterInfo, ServiceInfo, ServiceKeys, StatusInfo, SubscriptionInfo};
+use crate::{NsSearchInfo, Paged, ReplicaInfo, SyncInfo};
+use crate::{Response, ResponseKind};
+
+/// Error returned when the daemon responds with an unexpected response kind
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnexpectedResponse {
+    /// Name of the expected response kind
+    pub expected: &'static str,
+    /// Response kind returned by the daemon (boxed to keep `Result`s small)
+    pub got: Box<ResponseKind>,
+    /// Request ID for the response
+    pub req_id: u64,
+}
+
+impl UnexpectedResponse {
+    /// Fetch the daemon error where an error response was returned
+    pub fn error(&self) -> Option<&Error> {
+        match self.got.as_ref() {
+            ResponseKind::Error(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for UnexpectedResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unexpected response to request {}: expected {}, got {}",
+            self.req_id,
+            self.expected,
+            self.got.name()
+        )?;
+
+        match self.got.as_ref() {
+            ResponseKind::Error(e) => write!(f, " ({:?})", e)?,
+            ResponseKind::VersionMismatch(v) => write!(f, " ({})", v)?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnexpectedResponse {}
+
+impl ResponseKind {
+    /// Fetch the name of the response kind
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
+}
+
+impl Response {
+    /// Check for an empty (`None`) response
+    pub fn into_none(self) -> Result<(), UnexpectedResponse> {
+        match self.kind {
+            ResponseKind::None => Ok(()),
+            got => Err(UnexpectedResponse {
+                expected: "None",
+                got: Box::new(got),
+                req_id: self.req_id,
+            }),
+        }
+    }
+}
+
+/// Generate `Response::into_*` methods for payload carrying response kinds
+macro_rules! extract_methods {
+    ($($method:ident => $variant:ident($t:ty)),* $(,)?) => {
+        impl Response {
+            $(
+                #[doc = concat!("Extract the payload of a `", stringify!($variant), "` response")]
+                pub fn $method(self) -> Result<$t, UnexpectedResponse> {
+                    match self.kind {
+                        ResponseKind::$variant(v) => Ok(v),
+                        got => Err(UnexpectedResponse {
+                            expected: stringify!($variant),
+                            got: Box::new(got),
+                            req_id: self.req_id,
+                        }),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+/// Generate `Response::into_*` methods and `TryFrom<Response>` implementations
+/// for payload carrying response kinds
+macro_rules! extract {
+    ($($method:ident => $variant:ident($t:ty)),* $(,)?) => {
+        extract_methods!($($method => $variant($t)),*);
+
+        $(
+            impl TryFrom<Response> for $t {
+                type Error = UnexpectedResponse;
+
+                fn try_from(resp: Response) -> Result<Self, Self::Error> {
+                    resp.$method()
+                }
+            }
+        )*
+    };
+}
+
+extract!(
+    into_hello => Hello(HelloInfo),
+    into_version_mismatch => VersionMismatch(VersionMismatch),
+    into_status => Status(StatusInfo),
+    into_connected => Connected(ConnectInfo),
+    into_peer => Peer(PeerInfo),
+    into_peers => Peers(Paged<(Id, PeerInfo)>),
+    into_service => Service(ServiceInfo),
+    into_services => Services(Paged<ServiceInfo>),
+    into_keys => Keys(ServiceKeys),
+    into_registered => Registered(RegisterInfo),
+    into_located => Located(LocateInfo),
+    into_subscribed => Subscribed(Vec<SubscriptionInfo>),
+    into_subscribers => Subscribers(Paged<SubscriptionInfo>),
+    into_replicas => Replicas(Vec<ReplicaInfo>),
+    into_published => Published(PublishInfo),
+    into_datastore => Datastore(Vec<(Id, Vec<Vec<u8>>)>),
+    into_addresses => Addresses(Vec<AddressInfo>),
+    into_ns => Ns(NsRegisterInfo),
+    into_ns_search => NsSearch(NsSearchInfo),
+    into_ns_registrations => NsRegistrations(Paged<NsRegisterInfo>),
+    into_data => Data(Paged<DataInfo>),
+    into_query => Query(QueryInfo),
+    into_synced => Synced(SyncInfo),
+    into_pages => Pages(Vec<Container>),
+    into_page => Page(Container),
+    into_stream_open => StreamOpen(SubscriptionInfo),
+    into_stream_item => StreamItem(DataInfo),
+);
+
+// Payload types shared with other response kinds only provide `into_*` methods
+extract_methods!(
+    into_subscriber => Subscriber(SubscriptionInfo),
+);
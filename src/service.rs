@@ -1,5 +1,5 @@
 use std::net::SocketAddr;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use strum::{VariantNames};
 
@@ -9,7 +9,8 @@ use structopt::StructOpt;
 use dsf_core::types::*;
 
 pub use crate::helpers::{try_load_file, try_parse_key_value};
-use crate::{Body, ServiceIdentifier};
+use crate::helpers::parse_duration;
+use crate::{Body, Cursor, IndexOrder, PageBounds, PageError, Paged, ServiceIdentifier};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 //#[cfg_attr(feature = "diesel", derive(diesel::Queryable))]
@@ -20,8 +21,6 @@ pub struct ServiceInfo {
     pub state: ServiceState,
 
     pub public_key: PublicKey,
-    pub private_key: Option<PrivateKey>,
-    pub secret_key: Option<SecretKey>,
 
     pub last_updated: Option<SystemTime>,
 
@@ -43,8 +42,6 @@ impl From<&Service> for ServiceInfo {
             index: svc.version() as usize,
             state: ServiceState::Created,
             public_key: svc.public_key(),
-            private_key: svc.private_key(),
-            secret_key: svc.secret_key(),
 
             last_updated: None,
             primary_page: None,
@@ -58,6 +55,27 @@ impl From<&Service> for ServiceInfo {
     }
 }
 
+/// Service key material, only returned via an explicit `ServiceCommands::ExportKeys` request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ServiceKeys {
+    pub id: Id,
+
+    pub public_key: PublicKey,
+    pub private_key: Option<PrivateKey>,
+    pub secret_key: Option<SecretKey>,
+}
+
+impl From<&Service> for ServiceKeys {
+    fn from(svc: &Service) -> Self {
+        Self{
+            id: svc.id(),
+            public_key: svc.public_key(),
+            private_key: svc.private_key(),
+            secret_key: svc.secret_key(),
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize, Display)]
 #[cfg_attr(feature = "std", derive(EnumString))]
 pub enum ServiceState {
@@ -104,6 +122,10 @@ pub enum ServiceCommands {
     #[structopt(name = "remove")]
     /// Remove a service from the service list (and database if specified)
     Remove(RemoveOptions),
+
+    #[structopt(name = "export-keys")]
+    /// Export private and secret keys for a service
+    ExportKeys(ExportKeysOptions),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
@@ -111,6 +133,124 @@ pub struct ListOptions {
     #[structopt(long = "application-id")]
     /// Application ID for filtering
     pub application_id: Option<u16>,
+
+    #[structopt(flatten)]
+    #[serde(default)]
+    pub page_bounds: PageBounds,
+
+    #[structopt(long = "sort", default_value = "index", possible_values = ServiceSort::VARIANTS)]
+    #[serde(default)]
+    /// Sort services by the provided key
+    pub sort: ServiceSort,
+
+    #[structopt(long = "reverse")]
+    #[serde(default)]
+    /// Reverse sort order
+    pub reverse: bool,
+
+    #[structopt(long = "state")]
+    /// Filter services by state
+    pub state: Option<ServiceState>,
+
+    #[structopt(long = "origin")]
+    /// Filter services by origin (owned) status
+    pub origin: Option<bool>,
+
+    #[structopt(long = "subscribed")]
+    /// Filter services by subscription status
+    pub subscribed: Option<bool>,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self {
+            application_id: None,
+            page_bounds: PageBounds::default(),
+            sort: ServiceSort::Index,
+            reverse: false,
+            state: None,
+            origin: None,
+            subscribed: None,
+        }
+    }
+}
+
+impl ListOptions {
+    /// Check whether a service matches the list filters.
+    /// Note application IDs are not available in [`ServiceInfo`] and must be filtered separately
+    pub fn matches(&self, info: &ServiceInfo) -> bool {
+        if let Some(s) = self.state {
+            if info.state != s {
+                return false;
+            }
+        }
+
+        if let Some(o) = self.origin {
+            if info.origin != o {
+                return false;
+            }
+        }
+
+        if let Some(s) = self.subscribed {
+            if info.subscribed != s {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Sort services using the list sort key and order
+    pub fn sort(&self, services: &mut [ServiceInfo]) {
+        match self.sort {
+            ServiceSort::Index => services.sort_by_key(|s| s.index),
+            ServiceSort::Updated => services.sort_by_key(|s| s.last_updated),
+            ServiceSort::State => services.sort_by_key(|s| s.state as u8),
+            ServiceSort::Subscribers => services.sort_by_key(|s| s.subscribers),
+            ServiceSort::Replicas => services.sort_by_key(|s| s.replicas),
+        }
+
+        if self.reverse {
+            services.reverse();
+        }
+    }
+
+    /// Filter, sort and select a page of services
+    pub fn apply(&self, mut services: Vec<ServiceInfo>) -> Result<Paged<ServiceInfo>, PageError> {
+        services.retain(|s| self.matches(s));
+        self.sort(&mut services);
+
+        let order = match (self.sort, self.reverse) {
+            (ServiceSort::Index, false) => IndexOrder::Ascending,
+            (ServiceSort::Index, true) => IndexOrder::Descending,
+            _ => IndexOrder::Unordered,
+        };
+
+        Paged::select(services, &self.page_bounds, order, |s| Cursor::new(s.index))
+    }
+}
+
+/// Sort keys for service listings
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum ServiceSort {
+    #[default]
+    Index,
+    Updated,
+    State,
+    Subscribers,
+    Replicas,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
@@ -259,11 +399,18 @@ impl From<ServiceIdentifier> for InfoOptions {
 pub struct SubscribeOptions {
     #[structopt(flatten)]
     pub service: ServiceIdentifier,
+
+    #[structopt(flatten)]
+    #[serde(default)]
+    pub qos: Qos,
 }
 
 impl From<ServiceIdentifier> for SubscribeOptions {
     fn from(service: ServiceIdentifier) -> Self {
-        Self { service }
+        Self {
+            service,
+            qos: Qos::default(),
+        }
     }
 }
 
@@ -294,18 +441,77 @@ pub struct SubscriptionInfo {
     pub updated: Option<SystemTime>,
     pub expiry: Option<SystemTime>,
 
-    pub qos: QosPriority,
+    pub qos: Qos,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+)]
+#[strum(serialize_all = "snake_case")]
 pub enum QosPriority {
+    #[default]
     None = 0,
     Latency = 1,
 }
 
+/// Delivery guarantees for subscription data
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Default,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum Delivery {
+    /// Data is delivered once with no acknowledgement, and may be lost
+    #[default]
+    BestEffort,
+    /// Data is retained and re-delivered until acknowledged by the subscriber
+    AtLeastOnce,
+}
+
+/// Quality of service parameters requested by (and reported for) a subscription
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, StructOpt)]
+pub struct Qos {
+    #[structopt(long = "qos", default_value = "none", possible_values = QosPriority::VARIANTS)]
+    #[serde(default)]
+    /// Subscription priority
+    pub priority: QosPriority,
+
+    #[structopt(long = "delivery", default_value = "best_effort", possible_values = Delivery::VARIANTS)]
+    #[serde(default)]
+    /// Delivery guarantee, at-least-once delivery requires subscribers to acknowledge data
+    pub delivery: Delivery,
+
+    #[structopt(long = "rate-limit")]
+    /// Maximum number of deliveries per second
+    pub rate_limit: Option<u32>,
+
+    #[structopt(long = "batch-window", parse(try_from_str = parse_duration))]
+    /// Window over which data is batched prior to delivery
+    pub batch_window: Option<Duration>,
+
+    #[structopt(long = "max-backlog")]
+    /// Maximum number of undelivered objects retained, older objects are dropped
+    pub max_backlog: Option<usize>,
+}
+
 impl SubscriptionInfo {
     pub fn new(service_id: Id, kind: SubscriptionKind) -> Self {
-        Self{ service_id, kind, updated: None, expiry: None, qos: QosPriority::None }
+        Self{ service_id, kind, updated: None, expiry: None, qos: Qos::default() }
     }
 }
 
@@ -319,6 +525,18 @@ pub struct SetKeyOptions {
     pub secret_key: Option<SecretKey>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct ExportKeysOptions {
+    #[structopt(flatten)]
+    pub service: ServiceIdentifier,
+}
+
+impl From<ServiceIdentifier> for ExportKeysOptions {
+    fn from(service: ServiceIdentifier) -> Self {
+        Self { service }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
 pub struct RemoveOptions {
     #[structopt(flatten)]
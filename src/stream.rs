@@ -0,0 +1,117 @@
+//! Streaming RPC support.
+//!
+//! A `RequestKind::Stream` request is answered by a `StreamOpen` response, followed by
+//! `StreamItem` responses as data is published, all sharing the `req_id` of the request.
+//! Streams are terminated by a `StreamEnd` response, or a `StreamCancelled` response
+//! following a `RequestKind::StreamCancel` request from the client.
+//!
+//! Streams with at-least-once delivery (see [`crate::Delivery`]) re-deliver data until
+//! acknowledged by a `RequestKind::StreamAck` request.
+
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use futures::prelude::*;
+use structopt::StructOpt;
+
+use crate::{DataInfo, Request, RequestKind, Response, ResponseKind, Rpc};
+use crate::{SubscribeOptions, SubscriptionInfo, UnexpectedResponse};
+
+/// Stream of responses to a streaming request
+pub type ResponseStream = Pin<Box<dyn Stream<Item = Response> + Send>>;
+
+/// Stream of data objects published to a service, yielding an error where the stream
+/// is terminated by a response other than `StreamEnd` or `StreamCancelled`
+pub type DataStream = Pin<Box<dyn Stream<Item = Result<DataInfo, UnexpectedResponse>> + Send>>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct StreamCancelOptions {
+    #[structopt(long = "req-id")]
+    /// Request ID of the stream to be cancelled
+    pub req_id: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct StreamAckOptions {
+    #[structopt(long = "req-id")]
+    /// Request ID of the stream
+    pub req_id: u64,
+
+    #[structopt(long = "index")]
+    /// Index of the last data object received, acknowledging all prior objects
+    pub index: u16,
+}
+
+impl ResponseKind {
+    /// Check whether a response terminates a stream
+    pub fn is_stream_end(&self) -> bool {
+        matches!(
+            self,
+            ResponseKind::StreamEnd
+                | ResponseKind::StreamCancelled
+                | ResponseKind::Unrecognised
+                | ResponseKind::Unauthorized
+                | ResponseKind::Error(_)
+        )
+    }
+}
+
+/// Streaming extension to the [`Rpc`] trait
+#[async_trait]
+pub trait StreamRpc: Rpc {
+    /// Execute a streaming request, returning all responses matching the request `req_id`.
+    /// The returned stream completes following a stream terminating response
+    /// (see [`ResponseKind::is_stream_end`]).
+    ///
+    /// Implementations should cancel streams dropped prior to completion
+    /// (see [`StreamRpc::stream_cancel`]).
+    async fn exec_stream(&mut self, req: Request) -> Result<ResponseStream, Self::Error>;
+
+    /// Cancel a stream opened by the request with the provided `req_id`
+    async fn stream_cancel(&mut self, req_id: u64) -> Result<(), Self::Error> {
+        let req = Request::new(RequestKind::StreamCancel(StreamCancelOptions { req_id }));
+        Ok(self.exec(req).await?.into_none()?)
+    }
+
+    /// Acknowledge data objects up to and including `index` on the stream opened
+    /// by the request with the provided `req_id`
+    async fn stream_ack(&mut self, req_id: u64, index: u16) -> Result<(), Self::Error> {
+        let req = Request::new(RequestKind::StreamAck(StreamAckOptions { req_id, index }));
+        Ok(self.exec(req).await?.into_none()?)
+    }
+
+    /// Stream data published to a service, returning subscription information and
+    /// a stream of data objects, the stream is cancelled when the returned `DataStream` is dropped
+    async fn stream_data(
+        &mut self,
+        options: SubscribeOptions,
+    ) -> Result<(SubscriptionInfo, DataStream), Self::Error> {
+        let req = Request::new(RequestKind::Stream(options));
+        let req_id = req.req_id();
+
+        let mut s = self.exec_stream(req).await?;
+
+        // First response should acknowledge stream creation
+        let info = match s.next().await {
+            Some(resp) => resp.into_stream_open()?,
+            None => {
+                return Err(UnexpectedResponse {
+                    expected: "StreamOpen",
+                    got: Box::new(ResponseKind::StreamEnd),
+                    req_id,
+                }
+                .into())
+            }
+        };
+
+        // Surface unexpected (including error) responses, skipping normal stream termination
+        let data = s.filter_map(|resp| async move {
+            match &resp.kind {
+                ResponseKind::StreamEnd | ResponseKind::StreamCancelled => None,
+                _ => Some(resp.into_stream_item()),
+            }
+        });
+
+        Ok((info, Box::pin(data)))
+    }
+}
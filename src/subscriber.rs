@@ -1,11 +1,225 @@
-use structopt::StructOpt;
+use std::time::Duration;
 
+use structopt::clap::ArgGroup;
+use structopt::StructOpt;
+use strum::VariantNames;
 
+use dsf_core::types::*;
 
+use crate::helpers::parse_duration;
+use crate::{Delivery, PageBounds, Qos, QosPriority, ServiceIdentifier, SubscriptionKind};
 
-/// PeerState defines the state of a peer
+/// SubscriberCommands support management of subscribers to local services
 #[derive(Debug, Clone, StructOpt, PartialEq, Serialize, Deserialize, Display)]
 pub enum SubscriberCommands {
-    /// List all subscribers
-    List,
+    #[structopt(name = "list")]
+    /// List subscribers for a service
+    List(SubscriberListOptions),
+
+    #[structopt(name = "remove")]
+    /// Forcibly remove a subscriber from a service
+    Remove(SubscriberOptions),
+
+    #[structopt(name = "extend")]
+    /// Extend the expiry of a subscription
+    Extend(SubscriberExtendOptions),
+
+    #[structopt(name = "set-qos")]
+    /// Set QoS parameters for a subscription
+    SetQos(SubscriberQosOptions),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct SubscriberListOptions {
+    #[structopt(flatten)]
+    pub service: ServiceIdentifier,
+
+    #[structopt(flatten)]
+    #[serde(default)]
+    pub page_bounds: PageBounds,
+}
+
+impl From<ServiceIdentifier> for SubscriberListOptions {
+    fn from(service: ServiceIdentifier) -> Self {
+        Self {
+            service,
+            page_bounds: PageBounds::default(),
+        }
+    }
+}
+
+/// SubscriberOptions identify a subscriber to a service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+#[structopt(group = ArgGroup::with_name("subscriber").required(true))]
+pub struct SubscriberOptions {
+    #[structopt(flatten)]
+    pub service: ServiceIdentifier,
+
+    #[structopt(long = "peer-id", group = "subscriber")]
+    /// ID of a subscribed peer
+    pub peer_id: Option<Id>,
+
+    #[structopt(long = "socket-id", group = "subscriber")]
+    /// ID of a subscribed (local) socket
+    pub socket_id: Option<u32>,
+}
+
+impl SubscriberOptions {
+    /// Identify a peer subscribed to a service
+    pub fn peer(service: ServiceIdentifier, peer_id: Id) -> Self {
+        Self {
+            service,
+            peer_id: Some(peer_id),
+            socket_id: None,
+        }
+    }
+
+    /// Identify a (local) socket subscribed to a service
+    pub fn socket(service: ServiceIdentifier, socket_id: u32) -> Self {
+        Self {
+            service,
+            peer_id: None,
+            socket_id: Some(socket_id),
+        }
+    }
+
+    /// Fetch the kind of subscription identified by these options,
+    /// `None` where no subscriber is specified
+    pub fn kind(&self) -> Option<SubscriptionKind> {
+        match (&self.peer_id, self.socket_id) {
+            (Some(id), _) => Some(SubscriptionKind::Peer(id.clone())),
+            (None, Some(s)) => Some(SubscriptionKind::Socket(s)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct SubscriberExtendOptions {
+    #[structopt(flatten)]
+    pub subscriber: SubscriberOptions,
+
+    #[structopt(short = "d", long = "duration", parse(try_from_str = parse_duration))]
+    /// Duration to extend the subscription by
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, StructOpt)]
+pub struct SubscriberQosOptions {
+    #[structopt(flatten)]
+    pub subscriber: SubscriberOptions,
+
+    #[structopt(flatten)]
+    pub qos: QosUpdate,
+}
+
+/// Updated QoS parameters for a subscription, parameters that are not set are unchanged
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, StructOpt)]
+pub struct QosUpdate {
+    #[structopt(long = "qos", possible_values = QosPriority::VARIANTS)]
+    #[serde(default)]
+    /// Subscription priority
+    pub priority: Option<QosPriority>,
+
+    #[structopt(long = "delivery", possible_values = Delivery::VARIANTS)]
+    #[serde(default)]
+    /// Delivery guarantee, at-least-once delivery requires subscribers to acknowledge data
+    pub delivery: Option<Delivery>,
+
+    #[structopt(long = "rate-limit")]
+    #[serde(default)]
+    /// Maximum number of deliveries per second
+    pub rate_limit: Option<u32>,
+
+    #[structopt(long = "batch-window", parse(try_from_str = parse_duration))]
+    #[serde(default)]
+    /// Window over which data is batched prior to delivery
+    pub batch_window: Option<Duration>,
+
+    #[structopt(long = "max-backlog")]
+    #[serde(default)]
+    /// Maximum number of undelivered objects retained, older objects are dropped
+    pub max_backlog: Option<usize>,
+}
+
+impl QosUpdate {
+    /// Apply updated parameters to the existing QoS parameters for a subscription
+    pub fn apply(&self, qos: &mut Qos) {
+        if let Some(p) = &self.priority {
+            qos.priority = p.clone();
+        }
+        if let Some(d) = &self.delivery {
+            qos.delivery = d.clone();
+        }
+        if let Some(r) = self.rate_limit {
+            qos.rate_limit = Some(r);
+        }
+        if let Some(w) = self.batch_window {
+            qos.batch_window = Some(w);
+        }
+        if let Some(b) = self.max_backlog {
+            qos.max_backlog = Some(b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<SubscriberCommands, structopt::clap::Error> {
+        SubscriberCommands::from_iter_safe(["subscriber"].iter().chain(args))
+    }
+
+    #[test]
+    fn subscriber_required() {
+        assert!(parse(&["remove", "-n", "2"]).is_err());
+        assert!(parse(&["extend", "-n", "2", "-d", "10m"]).is_err());
+        assert!(parse(&["remove", "-n", "2", "--socket-id", "3", "--peer-id", "x"]).is_err());
+
+        let c = parse(&["remove", "-n", "2", "--socket-id", "3"]).unwrap();
+        let expected = SubscriberOptions::socket(ServiceIdentifier::index(2), 3);
+        assert_eq!(c, SubscriberCommands::Remove(expected.clone()));
+        assert_eq!(expected.kind(), Some(SubscriptionKind::Socket(3)));
+    }
+
+    #[test]
+    fn set_qos_updates_provided_fields() {
+        let c = parse(&[
+            "set-qos",
+            "-n",
+            "2",
+            "--socket-id",
+            "3",
+            "--rate-limit",
+            "5",
+        ])
+        .unwrap();
+        let update = match c {
+            SubscriberCommands::SetQos(o) => o.qos,
+            _ => panic!("unexpected command: {:?}", c),
+        };
+
+        assert_eq!(
+            update,
+            QosUpdate {
+                rate_limit: Some(5),
+                ..Default::default()
+            }
+        );
+
+        let mut qos = Qos {
+            priority: QosPriority::Latency,
+            delivery: Delivery::AtLeastOnce,
+            rate_limit: None,
+            batch_window: None,
+            max_backlog: Some(10),
+        };
+        update.apply(&mut qos);
+
+        assert_eq!(qos.priority, QosPriority::Latency);
+        assert_eq!(qos.delivery, Delivery::AtLeastOnce);
+        assert_eq!(qos.rate_limit, Some(5));
+        assert_eq!(qos.max_backlog, Some(10));
+    }
 }
@@ -0,0 +1,277 @@
+//! Table rendering for list responses.
+//!
+//! Tables align columns to their widest value, and where a maximum width is provided
+//! (typically the terminal width) the widest columns (usually IDs and keys) are
+//! truncated until the table fits.
+
+use std::fmt;
+
+use crate::{RenderError, Tabular};
+
+/// Minimum width to which columns are truncated
+pub const MIN_COLUMN_WIDTH: usize = 8;
+
+/// Separator between table columns
+const COLUMN_SEPARATOR: &str = "  ";
+
+/// Marker appended to truncated values
+const ELLIPSIS: char = '…';
+
+/// Table of rows with named columns
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    width: Option<usize>,
+}
+
+impl Table {
+    /// Create a new table with the provided headers and rows
+    pub fn new(headers: &[&str], rows: Vec<Vec<String>>) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows,
+            width: None,
+        }
+    }
+
+    /// Create a table from a [`Tabular`] object
+    pub fn from_tabular<T: Tabular>(v: &T) -> Self {
+        Self::new(&T::headers(), v.rows())
+    }
+
+    /// Select and re-order columns by name, an empty selection includes all columns
+    pub fn select(mut self, columns: &[String]) -> Result<Self, RenderError> {
+        if columns.is_empty() {
+            return Ok(self);
+        }
+
+        let mut indices = Vec::with_capacity(columns.len());
+        for c in columns {
+            match self.headers.iter().position(|h| h == c) {
+                Some(i) => indices.push(i),
+                None => return Err(RenderError::UnknownColumn(c.clone())),
+            }
+        }
+
+        self.headers = indices.iter().map(|i| self.headers[*i].clone()).collect();
+        self.rows = self
+            .rows
+            .iter()
+            .map(|r| {
+                indices
+                    .iter()
+                    .map(|i| r.get(*i).cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+
+        Ok(self)
+    }
+
+    /// Limit the table width, truncating the widest columns to fit
+    pub fn with_width(mut self, width: Option<usize>) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Compute column widths, shrinking the widest columns to fit the table width
+    pub fn column_widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for r in &self.rows {
+            for (w, v) in widths.iter_mut().zip(r.iter()) {
+                *w = (*w).max(v.chars().count());
+            }
+        }
+
+        let max = match self.width {
+            Some(m) => m,
+            None => return widths,
+        };
+
+        let separators = COLUMN_SEPARATOR.len() * widths.len().saturating_sub(1);
+
+        while widths.iter().sum::<usize>() + separators > max {
+            let (i, w) = match widths.iter().enumerate().max_by_key(|(_, w)| **w) {
+                Some((i, w)) => (i, *w),
+                None => break,
+            };
+
+            // Columns are not truncated beyond the minimum width, tables may still overflow
+            if w <= MIN_COLUMN_WIDTH {
+                break;
+            }
+
+            widths[i] -= 1;
+        }
+
+        widths
+    }
+
+    /// Render the table as comma separated values with a header row
+    pub fn csv(&self) -> String {
+        let mut lines = vec![self.headers.join(",")];
+        for r in &self.rows {
+            lines.push(
+                r.iter()
+                    .map(|v| csv_escape(v))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let widths = self.column_widths();
+
+        let line = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(widths.iter())
+                .map(|(c, w)| format!("{:1$}", truncate(c, *w), w))
+                .collect::<Vec<_>>()
+                .join(COLUMN_SEPARATOR)
+                .trim_end()
+                .to_string()
+        };
+
+        write!(f, "{}", line(&self.headers))?;
+        for r in &self.rows {
+            write!(f, "\n{}", line(r))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Truncate a value to the provided width, marking truncated values with an ellipsis
+pub fn truncate(v: &str, width: usize) -> String {
+    if v.chars().count() <= width {
+        return v.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut s: String = v.chars().take(width - 1).collect();
+    s.push(ELLIPSIS);
+    s
+}
+
+/// Quote CSV values containing separators, quotes or line breaks
+fn csv_escape(v: &str) -> String {
+    if v.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", v.replace('"', "\"\""))
+    } else {
+        v.to_string()
+    }
+}
+
+/// Fetch the terminal width, falling back to the `COLUMNS` environment variable.
+/// Returns `None` where neither is available (e.g. piped output), leaving tables untruncated.
+pub fn terminal_width() -> Option<usize> {
+    if let Some((terminal_size::Width(w), _)) = terminal_size::terminal_size() {
+        return Some(w as usize);
+    }
+
+    std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    fn table() -> Table {
+        Table::new(
+            &["id", "name", "peers"],
+            vec![
+                row(&["0123456789abcdef", "a", "3"]),
+                row(&["fedcba9876543210", "bb", "12"]),
+            ],
+        )
+    }
+
+    #[test]
+    fn align_columns() {
+        let t = table();
+        assert_eq!(t.column_widths(), vec![16, 4, 5]);
+        assert_eq!(
+            t.to_string(),
+            "id                name  peers\n\
+             0123456789abcdef  a     3\n\
+             fedcba9876543210  bb    12"
+        );
+    }
+
+    #[test]
+    fn truncate_columns() {
+        // The widest column is shrunk to fit
+        let t = table().with_width(Some(25));
+        assert_eq!(t.column_widths(), vec![12, 4, 5]);
+        assert_eq!(
+            t.to_string(),
+            "id            name  peers\n\
+             0123456789a…  a     3\n\
+             fedcba98765…  bb    12"
+        );
+
+        // Columns are not shrunk beyond the minimum width
+        let t = table().with_width(Some(10));
+        assert_eq!(t.column_widths(), vec![MIN_COLUMN_WIDTH, 4, 5]);
+        assert_eq!(
+            t.to_string(),
+            "id        name  peers\n\
+             0123456…  a     3\n\
+             fedcba9…  bb    12"
+        );
+    }
+
+    #[test]
+    fn truncate_values() {
+        assert_eq!(truncate("kitchen", 10), "kitchen");
+        assert_eq!(truncate("kitchen", 7), "kitchen");
+        assert_eq!(truncate("kitchen", 6), "kitch…");
+        assert_eq!(truncate("kitchen", 1), "…");
+        assert_eq!(truncate("kitchen", 0), "");
+        assert_eq!(truncate("", 0), "");
+    }
+
+    #[test]
+    fn select_columns() {
+        let t = table().select(&row(&["peers", "id"])).unwrap();
+        assert_eq!(t.csv(), "peers,id\n3,0123456789abcdef\n12,fedcba9876543210");
+
+        assert_eq!(table().select(&[]).unwrap(), table());
+
+        let r = table().select(&row(&["name", "missing"]));
+        assert!(matches!(r, Err(RenderError::UnknownColumn(c)) if c == "missing"));
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let t = Table::new(
+            &["name", "description"],
+            vec![
+                row(&["plain", "a, b"]),
+                row(&["quote", "say \"hi\""]),
+                row(&["lines", "one\ntwo\r\n"]),
+            ],
+        );
+
+        assert_eq!(
+            t.csv(),
+            "name,description\n\
+             plain,\"a, b\"\n\
+             quote,\"say \"\"hi\"\"\"\n\
+             lines,\"one\ntwo\r\n\""
+        );
+    }
+}